@@ -0,0 +1,34 @@
+//! The computer plays both sides of a game, printing the board after every move.
+//!
+//! Both sides play perfectly and break ties the same way, so the game is always the same draw.
+//!
+//! ```text
+//! cargo run --example self_play
+//! ```
+
+use anyhow::Result;
+
+use tictactoe::{Game, GameStatus};
+
+fn main() -> Result<()> {
+    let mut game = Game::new();
+    println!("{game}");
+    while game.status() == GameStatus::InProgress {
+        let player = game.get_player();
+        let Some(i) = game.best_move() else {
+            break;
+        };
+        println!("{player} plays {i} out of {:?}", game.available_moves());
+        game.play(i)?;
+        println!("{game}");
+    }
+
+    match game.status() {
+        GameStatus::Won(player)
+        | GameStatus::Resigned { winner: player }
+        | GameStatus::TimedOut { winner: player } => println!("The winner is {player}"),
+        GameStatus::Drawn => println!("It's a draw"),
+        GameStatus::InProgress => println!("The game was left unfinished"),
+    }
+    Ok(())
+}
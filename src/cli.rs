@@ -0,0 +1,629 @@
+//! The interactive terminal game loop, generic over its input and output
+
+#[cfg(feature = "serde")]
+use std::path::PathBuf;
+use std::{
+    io::{BufRead, Write},
+    num::ParseIntError,
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::{
+    game::{parse_algebraic, square_at, to_algebraic, Labels, RenderOptions},
+    score::Scoreboard,
+    Difficulty, Game, GameStatus, Player,
+};
+
+/// Settings for [`run_game_with`]
+#[derive(Debug, Clone)]
+pub struct CliOptions {
+    /// Require every move to be entered twice identically before it is played
+    pub confirm_moves: bool,
+    /// Enter moves as coordinates like `b2` instead of square numbers
+    pub algebraic: bool,
+    /// The side whose moves are picked by the computer, if any
+    pub computer: Option<Player>,
+    /// How strongly the computer plays
+    pub difficulty: Difficulty,
+    /// Where the `s` command saves the game when it isn't given a path
+    #[cfg(feature = "serde")]
+    pub save_path: PathBuf,
+}
+
+// Only derivable without the `serde` feature, which adds a save path with a default
+#[cfg_attr(not(feature = "serde"), allow(clippy::derivable_impls))]
+impl Default for CliOptions {
+    fn default() -> Self {
+        Self {
+            confirm_moves: false,
+            algebraic: false,
+            computer: None,
+            difficulty: Difficulty::default(),
+            #[cfg(feature = "serde")]
+            save_path: PathBuf::from("tictactoe.json"),
+        }
+    }
+}
+
+/// Plays games with [`run_game_with`], starting with `game` and continuing with new ones, until the
+/// players decline to play again. Prints the running score after each game and returns
+/// the final score.
+pub fn run_match<R: BufRead, W: Write>(
+    mut game: Game,
+    mut input: R,
+    mut output: W,
+    options: &CliOptions,
+) -> Result<Scoreboard> {
+    let mut scoreboard = Scoreboard::default();
+    loop {
+        let winner = run_game_with(&mut game, &mut input, &mut output, options)?;
+        scoreboard.record(winner);
+        writeln!(output, "{scoreboard}")?;
+
+        write!(output, "Play again? (y/n) ")?;
+        output.flush()?;
+        match read_line(&mut input, &mut output)?.as_deref() {
+            Some("y" | "yes") => game.reset(),
+            _ => return Ok(scoreboard),
+        }
+    }
+}
+
+/// Plays a game to the end, reading moves line by line from `input` and writing the board and
+/// prompts to `output`. Returns the winner, or [`None`] for a draw.
+///
+/// A player resigns by entering `q`, or when the input ends on their turn.
+///
+/// If the turns are timed, see [`Game::with_turn_limit`], a player who enters their move after
+/// their time is up forfeits, making the other player the winner.
+///
+/// This doesn't need a terminal, so a whole game can be driven from a string of moves:
+///
+/// ```
+/// use std::io::{sink, Cursor};
+///
+/// use tictactoe::{run_game, Game, Player};
+///
+/// let input = Cursor::new("1\n4\n2\n5\n3\n");
+/// let winner = run_game(&mut Game::new(), input, sink()).unwrap();
+/// assert_eq!(Some(Player::X), winner);
+/// ```
+pub fn run_game<R: BufRead, W: Write>(
+    game: &mut Game,
+    input: R,
+    output: W,
+) -> Result<Option<Player>> {
+    run_game_with(game, input, output, &CliOptions::default())
+}
+
+/// Plays a game like [`run_game`], with the given settings
+pub fn run_game_with<R: BufRead, W: Write>(
+    game: &mut Game,
+    mut input: R,
+    mut output: W,
+    options: &CliOptions,
+) -> Result<Option<Player>> {
+    let render_options = RenderOptions {
+        labels: if options.algebraic {
+            Labels::Algebraic
+        } else {
+            Labels::Numeric
+        },
+        ..Default::default()
+    };
+    let winner: Option<Player> = loop {
+        writeln!(output, "{}", game.render(&render_options))?;
+        if options.computer == Some(game.get_player()) {
+            if let Some(i) = game.ai_move(options.difficulty) {
+                game.make_move(i)?;
+                writeln!(output, "The computer plays {i}")?;
+            }
+        } else {
+            play_turn(game, &mut input, &mut output, options)?;
+        }
+        match game.status() {
+            GameStatus::Won(player)
+            | GameStatus::Resigned { winner: player }
+            | GameStatus::TimedOut { winner: player } => break Some(player),
+            GameStatus::Drawn => break None,
+            GameStatus::InProgress => {}
+        }
+    };
+    writeln!(output, "{}", game.render(&render_options))?;
+    match winner {
+        Some(winner) => writeln!(output, "The winner is {}", winner)?,
+        None => writeln!(output, "It's a draw")?,
+    }
+    if let Some(reason) = game.end_reason() {
+        writeln!(output, "{reason}")?;
+    }
+    Ok(winner)
+}
+
+/// Shows a recorded game play out for spectators, printing the board after every move of `moves`
+/// with a pause of `delay` before each move.
+///
+/// # Errors
+///
+/// If a move is illegal, before anything is printed.
+pub fn replay<W: Write>(moves: &[usize], mut output: W, delay: Duration) -> Result<()> {
+    let steps = Game::replay_steps(moves)?;
+    writeln!(output, "{}", Game::new())?;
+    for (game, i) in steps.iter().zip(moves) {
+        thread::sleep(delay);
+        writeln!(output, "{} plays {i}", game.get_player().next())?;
+        writeln!(output, "{game}")?;
+    }
+    if let Some(reason) = steps.last().and_then(Game::end_reason) {
+        writeln!(output, "{reason}")?;
+    }
+    Ok(())
+}
+
+/// What a player can enter on their turn, see [`parse_command`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Play the square with this number
+    Move(usize),
+    /// Take back the last move, `u` or `undo`
+    Undo,
+    /// Play the last move taken back again, `r` or `redo`
+    Redo,
+    /// Suggest a move, `h` or `hint`
+    Hint,
+    /// Save the game to the given file, or to the default one, `s [path]` or `save [path]`
+    #[cfg(feature = "serde")]
+    Save(Option<PathBuf>),
+    /// Resign the game, `q`, `quit` or `exit`
+    Quit,
+}
+
+/// Parses a line entered on a turn into a command, or a move given by its square number.
+///
+/// # Errors
+///
+/// If the line is neither.
+pub fn parse_command(input: &str) -> Result<Command> {
+    let input = input.trim();
+    let (word, rest) = input
+        .split_once(' ')
+        .map_or((input, ""), |(word, rest)| (word, rest.trim()));
+    match (word, rest) {
+        ("q" | "quit" | "exit", "") => Ok(Command::Quit),
+        ("h" | "hint", "") => Ok(Command::Hint),
+        ("u" | "undo", "") => Ok(Command::Undo),
+        ("r" | "redo", "") => Ok(Command::Redo),
+        #[cfg(feature = "serde")]
+        ("s" | "save", "") => Ok(Command::Save(None)),
+        #[cfg(feature = "serde")]
+        ("s" | "save", path) => Ok(Command::Save(Some(path.into()))),
+        _ => input
+            .parse()
+            .map(Command::Move)
+            .map_err(|_| anyhow!("{input} is neither a square number nor a command")),
+    }
+}
+
+/// Parses a line entered on a turn like [`parse_command`], also accepting moves as a row and a
+/// column like `2 3`, or as coordinates like `b2` instead of square numbers in algebraic mode
+fn parse_input(input: &str, size: usize, algebraic: bool) -> Result<Command> {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    match (parse_command(input), &tokens[..]) {
+        (Ok(Command::Move(_)) | Err(_), _) if algebraic => {
+            parse_algebraic(input, size).map(Command::Move)
+        }
+        (Err(_), &[row, col]) => {
+            let parse = |str: &str| str.parse().map_err(|err: ParseIntError| anyhow!(err));
+            square_at(parse(row)?, parse(col)?, size).map(Command::Move)
+        }
+        (command, _) => command,
+    }
+}
+
+/// Prompts the current player until they enter a legal move, take one back or resign, unless they
+/// run out of time first, which forfeits the game. The player resigns when the input ends.
+fn play_turn<R: BufRead, W: Write>(
+    game: &mut Game,
+    input: &mut R,
+    output: &mut W,
+    options: &CliOptions,
+) -> Result<()> {
+    let player = game.get_player();
+    loop {
+        write!(output, "It's {player}'s turn: ")?;
+        output.flush()?;
+        let Some(str) = read_line(input, output)? else {
+            writeln!(output, "The input ended")?;
+            game.resign(player);
+            return Ok(());
+        };
+        if game.forfeit_on_time() {
+            return Ok(());
+        }
+        // E.g. a line that couldn't be read
+        if str.is_empty() {
+            continue;
+        }
+        let command = match parse_input(&str, game.size(), options.algebraic) {
+            Ok(command) => command,
+            Err(err) => {
+                writeln!(output, "{err}")?;
+                continue;
+            }
+        };
+        match command {
+            Command::Quit => {
+                game.resign(player);
+                return Ok(());
+            }
+            Command::Hint => {
+                if let Some(i) = game.hint() {
+                    let square = if options.algebraic {
+                        to_algebraic(i, game.size())?
+                    } else {
+                        i.to_string()
+                    };
+                    writeln!(output, "Try square {square}")?;
+                }
+            }
+            Command::Undo => match game.undo() {
+                Ok(i) => {
+                    // Against the computer, also take back its reply to get the turn back
+                    if options.computer == Some(game.get_player()) {
+                        let _ = game.undo();
+                    }
+                    writeln!(output, "Took back {i}")?;
+                    return Ok(());
+                }
+                Err(err) => writeln!(output, "{err}")?,
+            },
+            Command::Redo => match game.redo() {
+                Ok(i) => {
+                    // Against the computer, also replay its reply
+                    if options.computer == Some(game.get_player()) {
+                        let _ = game.redo();
+                    }
+                    writeln!(output, "Played {i} again")?;
+                    return Ok(());
+                }
+                Err(err) => writeln!(output, "{err}")?,
+            },
+            #[cfg(feature = "serde")]
+            Command::Save(path) => {
+                let path = path.as_deref().unwrap_or(&options.save_path);
+                match game.save(path) {
+                    Ok(()) => writeln!(output, "Saved the game to {}", path.display())?,
+                    Err(err) => writeln!(output, "{err:#}")?,
+                }
+            }
+            Command::Move(i) => {
+                if options.confirm_moves {
+                    write!(output, "Enter {str} again to confirm: ")?;
+                    output.flush()?;
+                    if read_line(input, output)?.as_ref() != Some(&str) {
+                        writeln!(output, "The entries did not match, no move was played")?;
+                        continue;
+                    }
+                    if game.forfeit_on_time() {
+                        return Ok(());
+                    }
+                }
+                match game.make_move(i) {
+                    Ok(()) => return Ok(()),
+                    Err(err) => writeln!(output, "{err}")?,
+                }
+            }
+        }
+    }
+}
+
+/// Reads a trimmed line, or [`None`] if the input has ended. A line that cannot be read is
+/// reported and read as an empty line.
+fn read_line<R: BufRead, W: Write>(input: &mut R, output: &mut W) -> Result<Option<String>> {
+    let mut str = String::new();
+    match input.read_line(&mut str) {
+        Ok(0) => Ok(None),
+        Ok(_) => Ok(Some(str.trim().to_owned())),
+        Err(_) => {
+            writeln!(output, "Could not read the line")?;
+            Ok(Some(String::new()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufReader, Cursor, Read},
+        thread,
+    };
+
+    use super::*;
+
+    #[test]
+    fn headless_game() -> Result<()> {
+        let mut game = Game::new();
+        let mut output = Vec::new();
+        let winner = run_game(&mut game, Cursor::new("5\n1\n3\n7\n9\n4\n"), &mut output)?;
+        assert_eq!(Some(Player::O), winner);
+        assert_eq!(&[5, 1, 3, 7, 9, 4], game.moves());
+        assert!(String::from_utf8(output)?.contains("The winner is"));
+        Ok(())
+    }
+
+    #[test]
+    fn early_draw() -> Result<()> {
+        let mut game = Game::new();
+        let winner = run_game(
+            &mut game,
+            Cursor::new("1\n2\n4\n5\n8\n7\n3\n6\n"),
+            Vec::new(),
+        )?;
+        assert_eq!(None, winner);
+        assert_eq!(8, game.moves().len());
+        Ok(())
+    }
+
+    #[test]
+    fn confirmed_moves() -> Result<()> {
+        let mut game = Game::new();
+        let mut output = Vec::new();
+        let options = CliOptions {
+            confirm_moves: true,
+            ..Default::default()
+        };
+        let input = Cursor::new("5\n5\n1\n2\nq\n");
+        run_game_with(&mut game, input, &mut output, &options)?;
+
+        assert_eq!(Player::O, game.get_player());
+        assert_eq!(&[5], game.moves());
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("Enter 5 again to confirm: "));
+        assert!(output.contains("The entries did not match, no move was played"));
+        Ok(())
+    }
+
+    #[test]
+    fn algebraic_moves() -> Result<()> {
+        let mut game = Game::new();
+        let options = CliOptions {
+            algebraic: true,
+            ..Default::default()
+        };
+        let input = Cursor::new("5\nb2\nq\n");
+        run_game_with(&mut game, input, Vec::new(), &options)?;
+        assert_eq!(Player::O, game.get_player());
+        assert_eq!(&[5], game.moves());
+        Ok(())
+    }
+
+    #[test]
+    fn computer_opponent() -> Result<()> {
+        let mut game = Game::new();
+        let options = CliOptions {
+            computer: Some(Player::O),
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        run_game_with(&mut game, Cursor::new("1\nq\n"), &mut output, &options)?;
+        assert_eq!(Player::X, game.get_player());
+        assert!(String::from_utf8(output)?.contains("The computer plays 5"));
+        Ok(())
+    }
+
+    #[test]
+    fn undo_command() -> Result<()> {
+        let mut game = Game::new();
+        let mut output = Vec::new();
+        run_game(&mut game, Cursor::new("u\n5\n1\nu\nq\n"), &mut output)?;
+        assert_eq!(Player::O, game.get_player());
+        assert_eq!(&[5], game.moves());
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("There is no move to undo"));
+        assert!(output.contains("Took back 1"));
+        Ok(())
+    }
+
+    #[test]
+    fn hint_command() -> Result<()> {
+        let mut game = Game::new();
+        let mut output = Vec::new();
+        run_game(&mut game, Cursor::new("1\n4\n2\n5\nh\nq\n"), &mut output)?;
+        assert!(String::from_utf8(output)?.contains("Try square 3"));
+        assert_eq!(&[1, 4, 2, 5], game.moves());
+        assert_eq!(Player::X, game.get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn redo_command() -> Result<()> {
+        let mut game = Game::new();
+        let mut output = Vec::new();
+        run_game(&mut game, Cursor::new("r\n5\n1\nu\nr\nq\n"), &mut output)?;
+        assert_eq!(&[5, 1], game.moves());
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("There is no move to redo"));
+        assert!(output.contains("Played 1 again"));
+        Ok(())
+    }
+
+    #[test]
+    fn undo_against_computer() -> Result<()> {
+        let mut game = Game::new();
+        let options = CliOptions {
+            computer: Some(Player::O),
+            ..Default::default()
+        };
+        run_game_with(&mut game, Cursor::new("1\nu\nq\n"), Vec::new(), &options)?;
+        assert_eq!(Player::X, game.get_player());
+        assert!(game.moves().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn several_games() -> Result<()> {
+        let input = Cursor::new("1\n4\n2\n5\n3\ny\n1\n2\n3\n5\n8\n4\n6\n9\n7\nn\n");
+        let mut output = Vec::new();
+        let scoreboard = run_match(Game::new(), input, &mut output, &Default::default())?;
+        assert_eq!(
+            Scoreboard {
+                x_wins: 1,
+                o_wins: 0,
+                draws: 1
+            },
+            scoreboard
+        );
+        let output = String::from_utf8(output)?;
+        assert_eq!(2, output.matches("Play again? (y/n) ").count());
+        assert!(output.contains(&scoreboard.to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn resign_match() -> Result<()> {
+        let mut output = Vec::new();
+        let scoreboard = run_match(
+            Game::new(),
+            Cursor::new("5\nq\nn\n"),
+            &mut output,
+            &Default::default(),
+        )?;
+        let expected = Scoreboard {
+            x_wins: 1,
+            ..Default::default()
+        };
+        assert_eq!(expected, scoreboard);
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("O resigned"));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_commands() -> Result<()> {
+        assert_eq!(Command::Move(5), parse_command("5")?);
+        assert_eq!(Command::Undo, parse_command("u")?);
+        assert_eq!(Command::Redo, parse_command("redo")?);
+        assert_eq!(Command::Hint, parse_command("h")?);
+        assert_eq!(Command::Quit, parse_command(" q ")?);
+        let err = parse_command("x").unwrap_err();
+        assert_eq!(
+            "x is neither a square number nor a command",
+            err.to_string()
+        );
+        assert!(parse_command("u 2").is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn parse_save_command() -> Result<()> {
+        assert_eq!(
+            Command::Save(Some("foo.json".into())),
+            parse_command("save foo.json")?
+        );
+        assert_eq!(Command::Save(None), parse_command("s")?);
+        Ok(())
+    }
+
+    #[test]
+    fn row_column_moves() -> Result<()> {
+        let mut game = Game::new();
+        run_game(&mut game, Cursor::new("2 3\n4 1\n9\nq\n"), Vec::new())?;
+        assert_eq!(Player::X, game.get_player());
+        assert_eq!(&[6, 9], game.moves());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn save_command() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("game.json");
+        let mut game = Game::new();
+        let input = format!("5\ns {}\nq\n", path.display());
+        run_game(&mut game, Cursor::new(input), Vec::new())?;
+        let mut saved = Game::load(&path)?;
+        saved.resign(Player::O);
+        assert_eq!(game, saved);
+        Ok(())
+    }
+
+    #[test]
+    fn replay_game() -> Result<()> {
+        let mut output = Vec::new();
+        replay(&[1, 4, 2, 5, 3], &mut output, Duration::ZERO)?;
+        let output = String::from_utf8(output)?;
+        assert_eq!(6, output.matches(" ___________").count());
+        assert!(output.contains(&format!("{} plays 4", Player::O)));
+        assert!(output.contains(&format!("{} plays 3", Player::X)));
+        assert!(output.ends_with("completed the top row\n"));
+
+        let mut output = Vec::new();
+        assert!(replay(&[1, 1], &mut output, Duration::ZERO).is_err());
+        assert!(output.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn end_of_input_resigns() -> Result<()> {
+        let mut game = Game::new();
+        let mut output = Vec::new();
+        let winner = run_game(&mut game, Cursor::new("5\n"), &mut output)?;
+        assert_eq!(Some(Player::X), winner);
+        assert_eq!(&[5], game.moves());
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("The input ended"));
+        assert!(output.contains("O resigned"));
+        Ok(())
+    }
+
+    #[test]
+    fn empty_lines_reprompt() -> Result<()> {
+        let mut game = Game::new();
+        let winner = run_game(&mut game, Cursor::new("\n1\n\n4\n2\n5\n3\n"), Vec::new())?;
+        assert_eq!(Some(Player::X), winner);
+        Ok(())
+    }
+
+    /// Input that takes `delay` to arrive
+    struct SlowInput {
+        input: Cursor<&'static str>,
+        delay: Duration,
+    }
+
+    impl Read for SlowInput {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            thread::sleep(self.delay);
+            self.input.read(buf)
+        }
+    }
+
+    #[test]
+    fn running_out_of_time_forfeits() -> Result<()> {
+        let mut game = Game::new().with_turn_limit(Duration::from_millis(10));
+        let input = BufReader::new(SlowInput {
+            input: Cursor::new("5\n"),
+            delay: Duration::from_millis(50),
+        });
+        let mut output = Vec::new();
+        let winner = run_game(&mut game, input, &mut output)?;
+        assert_eq!(Some(Player::O), winner);
+        assert!(game.moves().is_empty());
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("X ran out of time"));
+        assert!(output.contains(&format!("The winner is {}", Player::O)));
+        assert_eq!(GameStatus::TimedOut { winner: Player::O }, game.status());
+        Ok(())
+    }
+
+    #[test]
+    fn moves_in_time() -> Result<()> {
+        let mut game = Game::new().with_turn_limit(Duration::from_secs(60));
+        let winner = run_game(&mut game, Cursor::new("1\n4\n2\n5\n3\n"), Vec::new())?;
+        assert_eq!(Some(Player::X), winner);
+        Ok(())
+    }
+}
@@ -0,0 +1,576 @@
+//! Game tree search over tic-tac-toe positions
+
+use std::{cmp::Reverse, collections::HashMap, str::FromStr};
+
+use anyhow::{anyhow, Result};
+use rand::{seq::IndexedRandom, Rng};
+
+use super::{Game, Player, SquareType, WinRule};
+
+/// How strongly the computer plays, see [`Game::ai_move`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    /// Plays any legal move at random
+    Easy,
+    /// Completes its own line or blocks the opponent's when it can, and plays at random otherwise.
+    /// Under [`WinRule::Misere`] it plays at random but avoids completing its own lines.
+    Medium,
+    /// Plays perfectly, like [`Game::best_move`], but opens with a random sound move on a 3x3 board
+    #[default]
+    Hard,
+}
+
+impl FromStr for Difficulty {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(Self::Easy),
+            "medium" => Ok(Self::Medium),
+            "hard" => Ok(Self::Hard),
+            _ => Err(anyhow!(
+                "Unknown difficulty {s}, expected easy, medium or hard"
+            )),
+        }
+    }
+}
+
+/// The result of a position with perfect play from both sides, see [`Game::evaluate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eval {
+    /// The side to move can force a win
+    Win,
+    /// The opponent can force a win
+    Loss,
+    /// Neither side can force a win
+    Draw,
+}
+
+/// Evaluates the position for the side to move with a full search
+pub fn evaluate(game: &Game) -> Eval {
+    match Search::default().score(game) {
+        score if score > 0 => Eval::Win,
+        score if score < 0 => Eval::Loss,
+        _ => Eval::Draw,
+    }
+}
+
+/// Picks a move for the side to move at the given difficulty, drawing random choices from `rng`,
+/// or returns [`None`] if the game is over.
+pub fn choose_move<R: Rng + ?Sized>(
+    game: &Game,
+    difficulty: Difficulty,
+    rng: &mut R,
+) -> Option<usize> {
+    if is_over(game) {
+        return None;
+    }
+    let player = game.next_player;
+    match difficulty {
+        Difficulty::Easy => game.available_moves().choose(rng).copied(),
+        Difficulty::Medium if game.win_rule == WinRule::Misere => {
+            let losing = completing_moves(game, player);
+            let moves = game.available_moves();
+            let safe: Vec<usize> = moves
+                .iter()
+                .copied()
+                .filter(|i| !losing.contains(i))
+                .collect();
+            safe.choose(rng).or_else(|| moves.choose(rng)).copied()
+        }
+        Difficulty::Medium => game
+            .winning_moves(player)
+            .first()
+            .or(game.winning_moves(player.next()).first())
+            .copied()
+            .or_else(|| game.available_moves().choose(rng).copied()),
+        Difficulty::Hard => opening_move(game, rng).or_else(|| game.best_move()),
+    }
+}
+
+/// The empty squares that would complete a line for `player`, which loses under the misère rule
+/// rather than winning like the [winning moves](Game::winning_moves)
+fn completing_moves(game: &Game, player: Player) -> Vec<usize> {
+    let mut normal = game.clone();
+    normal.win_rule = WinRule::Normal;
+    normal.winning_moves(player)
+}
+
+/// A random move from the book of sound openings of the standard game, or [`None`] once the board
+/// holds more than one piece. The book opens in the center or a corner, answers the center with a
+/// corner, and any other opening with the center.
+fn opening_move<R: Rng + ?Sized>(game: &Game, rng: &mut R) -> Option<usize> {
+    if (game.size, game.win_len, game.win_rule) != (3, 3, WinRule::Normal) {
+        return None;
+    }
+    let pieces: Vec<usize> = game
+        .cells()
+        .filter(|(_, square)| square.is_some())
+        .map(|(i, _)| i)
+        .collect();
+    let book: &[SquareType] = match pieces[..] {
+        [] => &[SquareType::Center, SquareType::Corner],
+        [i] => match game.square_type(i).ok()? {
+            SquareType::Center => &[SquareType::Corner],
+            SquareType::Corner | SquareType::Edge => &[SquareType::Center],
+        },
+        _ => return None,
+    };
+    let moves: Vec<usize> = game
+        .available_moves()
+        .into_iter()
+        .filter(|&i| game.square_type(i).is_ok_and(|kind| book.contains(&kind)))
+        .collect();
+    moves.choose(rng).copied()
+}
+
+/// Plays random legal moves for both sides on a copy of the game until a line is completed or the
+/// board is full
+pub fn random_playout<R: Rng + ?Sized>(game: &Game, rng: &mut R) -> Game {
+    let mut game = game.clone();
+    while !is_over(&game) {
+        let Some(&i) = game.available_moves().choose(rng) else {
+            break;
+        };
+        // Cannot fail, the square was picked among the available ones
+        let _ = game.make_move(i);
+    }
+    game
+}
+
+/// The legal move whose random playouts, `iterations` of them per move, score best for the side to
+/// move, counting a win as 2 and a draw as 1, or [`None`] if the game is over. Ties go to the
+/// lowest square.
+pub fn monte_carlo_move<R: Rng + ?Sized>(
+    game: &Game,
+    iterations: usize,
+    rng: &mut R,
+) -> Option<usize> {
+    if is_over(game) {
+        return None;
+    }
+    let player = game.next_player;
+    children(game)
+        .map(|(i, child)| {
+            let score: usize = (0..iterations)
+                .map(|_| match random_playout(&child, rng).get_winner() {
+                    Some(winner) if winner == player => 2,
+                    Some(_) => 0,
+                    None => 1,
+                })
+                .sum();
+            (i, score)
+        })
+        .min_by_key(|&(i, score)| (Reverse(score), i))
+        .map(|(i, _)| i)
+}
+
+/// Returns a move sequence forcing a win for the side to move, alternating between its moves and
+/// the opponent's best replies, or [`None`] if no forced win exists.
+///
+/// The opponent's replies are chosen to hold out as long as possible, so the line is the
+/// principal variation of the search and ends with the winning move.
+pub fn forced_win_line(game: &Game) -> Option<Vec<usize>> {
+    let mut search = Search::default();
+    if is_over(game) || search.score(game) <= 0 {
+        return None;
+    }
+
+    let mut line = Vec::new();
+    let mut game = game.clone();
+    while !is_over(&game) {
+        let (i, next) =
+            children(&game).max_by_key(|(i, child)| (-search.score(child), -(*i as i32)))?;
+        line.push(i);
+        game = next;
+    }
+    Some(line)
+}
+
+/// Scores every legal move for the side to move, best first, with ties in ascending square order.
+///
+/// A positive score means the move forces a win, a negative one that it loses against perfect
+/// play and 0 that it leads to a draw. Quicker wins and slower losses score further from 0.
+pub fn rank_moves(game: &Game) -> Vec<(usize, i32)> {
+    if is_over(game) {
+        return Vec::new();
+    }
+    let mut search = Search::default();
+    let mut ranked: Vec<_> = children(game)
+        .map(|(i, child)| (i, -search.score(&child)))
+        .collect();
+    ranked.sort_by_key(|&(i, score)| (-score, i));
+    ranked
+}
+
+/// Indicates whether the last move played scored as highly as the best move available at the
+/// time, or [`None`] if no move has been played yet.
+pub fn was_last_move_optimal(game: &Game) -> Option<bool> {
+    let mut before = game.clone();
+    let last = before.undo().ok()?;
+
+    let ranked = rank_moves(&before);
+    let best = ranked.first()?.1;
+    Some(ranked.contains(&(last, best)))
+}
+
+/// The fewest moves of its own the side to move needs to force a win, e.g. 1 when it can win
+/// right away, or [`None`] if it has no forced win.
+pub fn win_in(game: &Game) -> Option<usize> {
+    if is_over(game) {
+        return None;
+    }
+    let score = Search::default().score(game);
+    // A win on the side's own nth move leaves `empty - (2n - 1)` squares, scored one higher
+    (score > 0).then(|| (game.empty_count() + 2 - score as usize) / 2)
+}
+
+/// Plays `moves` on a copy of the game and counts the squares that would immediately win for the
+/// player whose turn it is afterwards.
+///
+/// # Errors
+///
+/// If any move of the sequence is illegal, including moves made after the game is over.
+pub fn threats_after_sequence(game: &Game, moves: &[usize]) -> Result<usize> {
+    let mut game = game.clone();
+    for &i in moves {
+        if is_over(&game) {
+            return Err(anyhow!("Cannot play {i}, the game is already over"));
+        }
+        game.make_move(i)?;
+    }
+    Ok(game.winning_moves(game.next_player).len())
+}
+
+/// A negamax search remembering the score of every position it has seen
+#[derive(Default)]
+struct Search {
+    /// Scores by [position key](Game::position_key)
+    scores: HashMap<u64, i32>,
+}
+
+impl Search {
+    /// Scores the position for the side to move: positive if it can force a win, negative if it
+    /// loses against perfect play and 0 for a draw. Quicker wins and slower losses score further
+    /// from 0.
+    fn score(&mut self, game: &Game) -> i32 {
+        if let Some(winner) = game.get_winner() {
+            // The more squares left the quicker the game was decided
+            let score = game.empty_count() as i32 + 1;
+            return if winner == game.next_player {
+                score
+            } else {
+                -score
+            };
+        }
+        let key = game.position_key();
+        if let Some(&score) = self.scores.get(&key) {
+            return score;
+        }
+        let score = children(game)
+            .map(|(_, child)| -self.score(&child))
+            .max()
+            .unwrap_or(0);
+        self.scores.insert(key, score);
+        score
+    }
+}
+
+/// Every legal move of the side to move, along with the position it leads to
+fn children(game: &Game) -> impl Iterator<Item = (usize, Game)> + '_ {
+    game.available_moves().into_iter().filter_map(|i| {
+        let mut child = game.clone();
+        child.make_move(i).ok().map(|_| (i, child))
+    })
+}
+
+fn is_over(game: &Game) -> bool {
+    game.get_winner().is_some() || game.is_full() || game.forfeited().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{GameStatus, Player::*, WinRule};
+
+    #[test]
+    fn win_in_one_line() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![Some(O), Some(O), None],
+            vec![None, None, None],
+        ];
+        assert_eq!(Some(vec![3]), forced_win_line(&game));
+    }
+
+    #[test]
+    fn forced_win_line_wins() -> Result<()> {
+        // O answered the corner opening on an adjacent edge, which loses by force
+        let mut game = Game::new();
+        game.make_move(1)?;
+        game.make_move(2)?;
+        let line = forced_win_line(&game).expect("X has a forced win");
+        for i in line {
+            game.make_move(i)?;
+        }
+        assert_eq!(Some(X), game.get_winner());
+        Ok(())
+    }
+
+    #[test]
+    fn suboptimal_last_move() -> Result<()> {
+        let mut game = Game::new();
+        assert_eq!(None, was_last_move_optimal(&game));
+        game.make_move(1)?;
+        // Answering a corner on an adjacent edge loses
+        game.make_move(2)?;
+        assert_eq!(Some(false), was_last_move_optimal(&game));
+        game.make_move(5)?;
+        assert_eq!(Some(true), was_last_move_optimal(&game));
+        Ok(())
+    }
+
+    #[test]
+    fn ranked_moves() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![Some(O), Some(O), None],
+            vec![None, None, None],
+        ];
+        let ranked = rank_moves(&game);
+        assert_eq!(5, ranked.len());
+        assert_eq!(3, ranked[0].0);
+        assert!(ranked[0].1 > 0);
+    }
+
+    #[test]
+    fn win_in_one() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![Some(O), Some(O), None],
+            vec![None, None, None],
+        ];
+        assert_eq!(Some(1), win_in(&game));
+    }
+
+    #[test]
+    fn win_in_two() -> Result<()> {
+        let mut game = Game::new();
+        for i in [1, 2, 5, 9] {
+            game.make_move(i)?;
+        }
+        assert_eq!(Some(2), win_in(&game));
+        Ok(())
+    }
+
+    #[test]
+    fn no_win_in() {
+        assert_eq!(None, win_in(&Game::new()));
+    }
+
+    #[test]
+    fn fork_after_sequence() -> Result<()> {
+        let mut game = Game::new();
+        for i in [1, 2, 5, 9] {
+            game.make_move(i)?;
+        }
+        assert_eq!(0, threats_after_sequence(&game, &[])?);
+        assert_eq!(2, threats_after_sequence(&game, &[4, 3])?);
+        Ok(())
+    }
+
+    #[test]
+    fn illegal_sequence() -> Result<()> {
+        let mut game = Game::new();
+        game.make_move(5)?;
+        assert!(threats_after_sequence(&game, &[1, 5]).is_err());
+        assert!(threats_after_sequence(&game, &[10]).is_err());
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), Some(X)],
+            vec![Some(O), Some(O), None],
+            vec![None, None, None],
+        ];
+        assert!(threats_after_sequence(&game, &[6]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn no_forced_win() {
+        assert_eq!(None, forced_win_line(&Game::new()));
+    }
+
+    #[test]
+    fn hard_blocks() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![None, Some(O), None],
+            vec![None, None, None],
+        ];
+        game.next_player = O;
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(Some(3), choose_move(&game, Difficulty::Hard, &mut rng));
+        assert_eq!(Some(3), game.ai_move(Difficulty::Hard));
+    }
+
+    #[test]
+    fn book_openings() -> Result<()> {
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let game = Game::new();
+            let i = choose_move(&game, Difficulty::Hard, &mut rng).expect("the board is empty");
+            assert!([1, 3, 5, 7, 9].contains(&i));
+
+            // None of the replies to the opening lose
+            for opening in 1..=9 {
+                let mut game = Game::new();
+                game.make_move(opening)?;
+                let reply = choose_move(&game, Difficulty::Hard, &mut rng).expect("not over");
+                game.make_move(reply)?;
+                assert_eq!(Eval::Draw, evaluate(&game), "{opening} then {reply}");
+            }
+        }
+        // Past the opening the search takes over
+        let game = Game::from_moves(&[1, 5])?;
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(
+            game.best_move(),
+            choose_move(&game, Difficulty::Hard, &mut rng)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn random_playouts_end() -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            let (game, status) = Game::random_playout(&mut rng);
+            assert!(game.get_winner().is_some() || game.is_full());
+            assert_eq!(game.status(), status);
+            assert_ne!(GameStatus::InProgress, status);
+        }
+
+        let start = Game::from_moves(&[5, 1])?;
+        let game = random_playout(&start, &mut rng);
+        assert_eq!(&[5, 1], &game.moves()[..2]);
+        assert_eq!(&[5, 1], start.moves());
+        Ok(())
+    }
+
+    #[test]
+    fn monte_carlo_wins_then_blocks() -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![Some(O), Some(O), None],
+            vec![None, None, None],
+        ];
+        assert_eq!(Some(3), game.mcts_move(200, &mut rng));
+        game.next_player = O;
+        assert_eq!(Some(6), game.mcts_move(200, &mut rng));
+
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![None, Some(O), None],
+            vec![None, None, None],
+        ];
+        assert_eq!(Some(3), game.mcts_move(200, &mut rng));
+        let won = Game::from_moves(&[1, 4, 2, 5, 3])?;
+        assert_eq!(None, won.mcts_move(200, &mut rng));
+        Ok(())
+    }
+
+    #[test]
+    fn medium_wins_then_blocks() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![Some(O), Some(O), None],
+            vec![None, None, None],
+        ];
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(Some(3), choose_move(&game, Difficulty::Medium, &mut rng));
+        // Winning beats blocking
+        game.next_player = O;
+        assert_eq!(Some(6), choose_move(&game, Difficulty::Medium, &mut rng));
+        game.arr_squares[1][0] = None;
+        assert_eq!(Some(3), choose_move(&game, Difficulty::Medium, &mut rng));
+    }
+
+    #[test]
+    fn easy_plays_legal_moves() -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut game = Game::new();
+        while let Some(i) = choose_move(&game, Difficulty::Easy, &mut rng) {
+            assert!(game.available_moves().contains(&i));
+            game.make_move(i)?;
+        }
+        assert!(is_over(&game));
+        assert_eq!(None, game.ai_move(Difficulty::Easy));
+
+        // The same seed replays the same game
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut replay = Game::new();
+        while let Some(i) = choose_move(&replay, Difficulty::Easy, &mut rng) {
+            replay.make_move(i)?;
+        }
+        assert_eq!(game.moves(), replay.moves());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_difficulty() -> Result<()> {
+        assert_eq!(Difficulty::Easy, "easy".parse()?);
+        assert_eq!(Difficulty::Medium, "Medium".parse()?);
+        assert_eq!(Difficulty::Hard, "HARD".parse()?);
+        assert!("impossible".parse::<Difficulty>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn misere_medium_avoids_lines() {
+        let mut game = Game::new();
+        game.win_rule = WinRule::Misere;
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![Some(O), None, None],
+            vec![Some(O), None, None],
+        ];
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let i = choose_move(&game, Difficulty::Medium, &mut rng);
+            assert!(i.is_some_and(|i| i != 3), "{i:?}");
+        }
+    }
+
+    #[test]
+    fn misere_avoids_lines() -> Result<()> {
+        let mut game = Game::builder().win_rule(WinRule::Misere).build()?;
+        for i in [1, 4, 2, 5] {
+            game.make_move(i)?;
+        }
+        let ranked = rank_moves(&game);
+        assert_ne!(3, ranked[0].0);
+        assert!(ranked.iter().any(|&(i, score)| i == 3 && score < 0));
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_positions() -> Result<()> {
+        assert_eq!(Eval::Draw, evaluate(&Game::new()));
+        assert_eq!(Eval::Win, evaluate(&Game::from_moves(&[1, 4, 2, 5])?));
+        // O answered the corner opening on an adjacent edge and can't stop X anymore
+        assert_eq!(Eval::Loss, evaluate(&Game::from_moves(&[1, 2, 5])?));
+        // X already won
+        assert_eq!(Eval::Loss, evaluate(&Game::from_moves(&[1, 4, 2, 5, 3])?));
+        let full = Game::from_moves(&[1, 2, 3, 5, 8, 4, 6, 9, 7])?;
+        assert_eq!(Eval::Draw, full.evaluate());
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::{anyhow, Result};
+
+use super::{get_coords, Game, Player, WinRule};
+
+/// Sets up a [`Game`] that doesn't start from the empty board or plays by other rules
+#[derive(Debug, Default, Clone)]
+pub struct GameBuilder {
+    handicap: Vec<(usize, Player)>,
+    placed: Vec<(usize, Player)>,
+    first_player: Option<Player>,
+    win_rule: WinRule,
+}
+
+impl GameBuilder {
+    /// Decides whether completing a line wins or loses, see [`WinRule`]
+    pub fn win_rule(mut self, rule: WinRule) -> Self {
+        self.win_rule = rule;
+        self
+    }
+
+    /// Pre-places pieces on the given squares, e.g. to give a weaker player a head start
+    pub fn handicap(mut self, pieces: &[(usize, Player)]) -> Self {
+        self.handicap.extend_from_slice(pieces);
+        self
+    }
+
+    /// Puts a piece of `player` on square `i`, to set up a position that could come up in a game
+    pub fn place(mut self, i: usize, player: Player) -> Self {
+        self.placed.push((i, player));
+        self
+    }
+
+    /// The player who made the first move, [`Player::X`] unless chosen otherwise
+    pub fn first_player(mut self, player: Player) -> Self {
+        self.first_player = Some(player);
+        self
+    }
+
+    /// Creates the game.
+    ///
+    /// Without a handicap, the players are assumed to have taken turns from the first player on,
+    /// which decides whose turn it is. A handicap unbalances the pieces on purpose, so with one
+    /// the player with fewer pieces on the board moves next, or the first player if both have the
+    /// same number.
+    ///
+    /// # Errors
+    ///
+    /// If a square is out of range or used twice. With a handicap, if the pieces already complete
+    /// a line, and otherwise if the position is rejected by [`Game::validate`].
+    pub fn build(self) -> Result<Game> {
+        let first = self.first_player.unwrap_or(Player::X);
+        let mut game = Game {
+            win_rule: self.win_rule,
+            first_player: first,
+            next_player: first,
+            ..Game::new()
+        };
+        for &(i, player) in self.handicap.iter().chain(&self.placed) {
+            let (y, x) = get_coords(i, game.size)?;
+            let square = &mut game.arr_squares[y][x];
+            if square.is_some() {
+                return Err(anyhow!("Square {i} is placed more than once"));
+            }
+            *square = Some(player);
+        }
+
+        if self.handicap.is_empty() {
+            if game.piece_count(first) > game.piece_count(first.next()) {
+                game.next_player = first.next();
+            }
+            game.validate()?;
+            return Ok(game);
+        }
+
+        if let Some(player) = game.get_winner() {
+            return Err(anyhow!("The pieces already make {player} the winner"));
+        }
+        if game.piece_count(first.next()) < game.piece_count(first) {
+            game.next_player = first.next();
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Player::*;
+
+    #[test]
+    fn handicapped_game() -> Result<()> {
+        let game = Game::builder().handicap(&[(5, X), (1, X)]).build()?;
+        assert_eq!(
+            vec![
+                vec![Some(X), None, None],
+                vec![None, Some(X), None],
+                vec![None, None, None],
+            ],
+            game.arr_squares
+        );
+        assert_eq!(O, game.get_player());
+
+        let game = Game::builder().handicap(&[(9, O)]).build()?;
+        assert_eq!(Some(O), game.arr_squares[2][2]);
+        assert_eq!(X, game.get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_handicap() {
+        assert!(Game::builder().handicap(&[(10, X)]).build().is_err());
+        assert!(Game::builder().handicap(&[(5, X), (5, O)]).build().is_err());
+        assert!(Game::builder()
+            .handicap(&[(1, O), (2, O), (3, O)])
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn placed_position() -> Result<()> {
+        let game = Game::builder()
+            .place(5, X)
+            .place(1, O)
+            .place(9, X)
+            .build()?;
+        let played = Game::from_moves(&[5, 1, 9])?;
+        assert_eq!(played.arr_squares, game.arr_squares);
+        assert_eq!(O, game.get_player());
+
+        let game = Game::builder().first_player(O).place(5, O).build()?;
+        assert_eq!(X, game.get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn illegal_position() {
+        assert!(Game::builder().place(1, X).place(2, X).build().is_err());
+        assert!(Game::builder().first_player(O).place(5, X).build().is_err());
+        assert!(Game::builder().place(5, X).place(5, O).build().is_err());
+        assert!(Game::builder()
+            .place(1, X)
+            .place(4, O)
+            .place(2, X)
+            .place(5, O)
+            .place(3, X)
+            .place(6, O)
+            .build()
+            .is_err());
+    }
+}
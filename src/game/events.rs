@@ -0,0 +1,141 @@
+//! Callbacks notified as a game is played, see [`Game::on_move`]
+
+use std::fmt;
+
+use super::{Game, GameStatus, Player};
+
+/// Called with the square and the player of every move
+pub type MoveObserver = Box<dyn FnMut(usize, Player) + Send>;
+/// Called with the final status once a move, a resignation or a timeout ends the game
+pub type EndObserver = Box<dyn FnMut(GameStatus) + Send>;
+
+/// The callbacks registered on a game.
+///
+/// They belong to the game they were registered on: clones of the game, e.g. the positions an AI
+/// searches through, start without any, and they are ignored when comparing games.
+#[derive(Default)]
+pub(super) struct Observers {
+    moves: Vec<MoveObserver>,
+    ends: Vec<EndObserver>,
+}
+
+impl Observers {
+    /// Whether any observer wants to know when the game ends
+    pub(super) fn watches_end(&self) -> bool {
+        !self.ends.is_empty()
+    }
+
+    /// Tells every observer about the move just played on `game`. The end observers are only told
+    /// if the game was still going before the move, see [`Observers::watches_end`].
+    pub(super) fn notify(&mut self, game: &Game, i: usize, player: Player, was_over: bool) {
+        for observer in &mut self.moves {
+            observer(i, player);
+        }
+        if !was_over {
+            self.notify_end(game);
+        }
+    }
+
+    /// Tells the end observers about the final status of `game`, if it is over
+    pub(super) fn notify_end(&mut self, game: &Game) {
+        let status = game.status();
+        if status != GameStatus::InProgress {
+            for observer in &mut self.ends {
+                observer(status);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observers")
+            .field("moves", &self.moves.len())
+            .field("ends", &self.ends.len())
+            .finish()
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for Observers {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Game {
+    /// Calls `observer` with the square and the player after every successful move, e.g. to
+    /// update a user interface
+    pub fn on_move(&mut self, observer: MoveObserver) {
+        self.observers.moves.push(observer);
+    }
+
+    /// Calls `observer` with the final status once a move wins or draws the game, or a player
+    /// resigns or runs out of time
+    pub fn on_end(&mut self, observer: EndObserver) {
+        self.observers.ends.push(observer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::Player::*;
+
+    #[test]
+    fn observe_moves() -> Result<()> {
+        let moves = Arc::new(Mutex::new(Vec::new()));
+        let ends = Arc::new(Mutex::new(Vec::new()));
+        let mut game = Game::new();
+        let log = Arc::clone(&moves);
+        game.on_move(Box::new(move |i, player| {
+            log.lock().unwrap().push((i, player))
+        }));
+        let log = Arc::clone(&ends);
+        game.on_end(Box::new(move |status| log.lock().unwrap().push(status)));
+
+        for i in [1, 4, 2] {
+            game.make_move(i)?;
+        }
+        assert!(game.make_move(4).is_err());
+        // The search of the best move plays on copies of the game
+        assert_eq!(Some(3), game.best_move());
+        assert_eq!(vec![(1, X), (4, O), (2, X)], *moves.lock().unwrap());
+        assert!(ends.lock().unwrap().is_empty());
+
+        game.make_move(5)?;
+        game.make_move(3)?;
+        assert_eq!(5, moves.lock().unwrap().len());
+        assert_eq!(vec![GameStatus::Won(X)], *ends.lock().unwrap());
+
+        // Moves after the end don't end the game again
+        game.make_move(9)?;
+        assert_eq!(6, moves.lock().unwrap().len());
+        assert_eq!(vec![GameStatus::Won(X)], *ends.lock().unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn observe_early_draw() -> Result<()> {
+        let ends = Arc::new(Mutex::new(Vec::new()));
+        let mut game = Game::new();
+        let log = Arc::clone(&ends);
+        game.on_end(Box::new(move |status| log.lock().unwrap().push(status)));
+
+        // The draw is inevitable after 6, the board is full after 9
+        for i in [1, 2, 4, 5, 8, 7, 3, 6, 9] {
+            game.make_move(i)?;
+        }
+        assert_eq!(vec![GameStatus::Drawn], *ends.lock().unwrap());
+        Ok(())
+    }
+}
@@ -1,303 +1,2104 @@
 //! A 2-player tic-tac-toe game
 
+pub mod ai;
+mod builder;
+mod events;
 mod player;
+mod render;
+#[cfg(feature = "serde")]
+mod save;
+pub mod transcript;
 
-use std::fmt::Display;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
 
 use anyhow::{anyhow, Ok, Result};
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
+pub use ai::{Difficulty, Eval};
+pub use builder::GameBuilder;
+use events::Observers;
+pub use events::{EndObserver, MoveObserver};
 pub use player::Player;
+pub use render::{render_side_by_side, Labels, RenderMode, RenderOptions};
+
+/// The squares of a board, row by row
+type Board = Vec<Vec<Option<Player>>>;
+
+/// The state of the game right after a move, see [`Game::play`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveOutcome {
+    /// The game goes on with the other player's turn
+    Continue,
+    /// The move completed a line for this player
+    Win(Player),
+    /// No one won and no one can win anymore, see [`GameStatus::Drawn`]
+    Draw,
+}
+
+/// Why a move couldn't be played, see [`Game::make_move`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// There is no square with this number on the board
+    OutOfRange(usize),
+    /// The square is already taken
+    Occupied { square: usize, by: Player },
+    /// A player resigned or ran out of time, so no more moves can be made
+    GameOver,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange(i) => write!(f, "There is no square {i} on the board"),
+            Self::Occupied { square, by } => write!(f, "Tile {square} is already filled by {by}"),
+            Self::GameOver => write!(f, "The game is already over"),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+/// What completing a line means, see [`GameBuilder::win_rule`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WinRule {
+    /// The player who completes a line wins
+    #[default]
+    Normal,
+    /// The player who completes a line loses, also known as reverse tic-tac-toe
+    Misere,
+}
+
+/// Where a square lies on the board, see [`Game::square_type`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SquareType {
+    /// The middle of a board with an odd size
+    Center,
+    /// One of the four squares at the ends of both diagonals
+    Corner,
+    /// Any other square, e.g. the middle of a side on a 3x3 board
+    Edge,
+}
+
+/// Where a game stands, see [`Game::status`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    /// A line can still be completed
+    InProgress,
+    /// This player completed a line
+    Won(Player),
+    /// No one won and no one can win anymore, either because the board is full or because every
+    /// line already holds pieces of both players
+    Drawn,
+    /// The other player resigned, see [`Game::resign`]
+    Resigned { winner: Player },
+    /// The other player ran out of time, see [`Game::forfeit_on_time`]
+    TimedOut { winner: Player },
+}
 
 /// Represents the game board itself
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Game {
+    /// Filled in from the board when missing, see [`Game::from_json`]
+    #[cfg_attr(feature = "serde", serde(default = "save::default_first_player"))]
     next_player: Player,
-    arr_squares: [[Option<Player>; 3]; 3],
-    last_played: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default = "save::default_first_player"))]
+    first_player: Player,
+    #[cfg_attr(feature = "serde", serde(rename = "board"))]
+    arr_squares: Board,
+    history: Vec<usize>,
+    /// Moves taken back with [`Game::undo`], the most recent last
+    #[cfg_attr(feature = "serde", serde(default))]
+    undone: Vec<usize>,
+    size: usize,
+    win_len: usize,
+    #[cfg_attr(feature = "serde", serde(default = "save::default_symbols"))]
+    symbols: [char; 2],
+    #[cfg_attr(feature = "serde", serde(default))]
+    win_rule: WinRule,
+    #[cfg_attr(feature = "serde", serde(default))]
+    turn_limit: Option<Duration>,
+    /// When the current turn began, if the turns are timed
+    #[cfg_attr(feature = "serde", serde(skip))]
+    turn_started: Option<Instant>,
+    /// The player who gave up the game, see [`Game::resign`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    resigned: Option<Player>,
+    /// The player who ran out of time, see [`Game::forfeit_on_time`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    timed_out: Option<Player>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observers: Observers,
 }
 
-impl Display for Game {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, " ___________")?;
-        writeln!(f, "|   |   |   |")?;
-        for i in 1..=9 {
-            let (y, x) = get_coords(i).map_err(|_| std::fmt::Error)?;
-            if y != 0 && x == 0 {
-                writeln!(f, "|___|___|___|")?;
-                writeln!(f, "|   |   |   |")?;
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
+    /// Creates a new 3x3 game with an empty board and next player set to [`Player::X`]
+    pub fn new() -> Self {
+        Self::with_size(3, 3)
+    }
+
+    /// Creates a new game on an empty `n`x`n` board, won by completing a horizontal, vertical or
+    /// diagonal run of `win_len` squares. The next player is set to [`Player::X`].
+    ///
+    /// # Panics
+    ///
+    /// If `win_len` is 0 or larger than `n`.
+    pub fn with_size(n: usize, win_len: usize) -> Self {
+        assert!(
+            (1..=n).contains(&win_len),
+            "The win length must be between 1 and the board size"
+        );
+
+        Self {
+            next_player: Player::X,
+            first_player: Player::X,
+            arr_squares: vec![vec![None; n]; n],
+            history: Vec::new(),
+            undone: Vec::new(),
+            size: n,
+            win_len,
+            symbols: [Player::X.to_char(), Player::O.to_char()],
+            win_rule: WinRule::Normal,
+            turn_limit: None,
+            turn_started: None,
+            resigned: None,
+            timed_out: None,
+            observers: Observers::default(),
+        }
+    }
+
+    /// Gives every turn at most `limit` to be played, starting the clock of the current turn now.
+    /// A player who runs out of time forfeits the game in [`run_game`](crate::run_game).
+    pub fn with_turn_limit(self, limit: Duration) -> Self {
+        Self {
+            turn_limit: Some(limit),
+            turn_started: Some(Instant::now()),
+            ..self
+        }
+    }
+
+    /// Whether completing a line wins or loses the game
+    pub fn win_rule(&self) -> WinRule {
+        self.win_rule
+    }
+
+    /// The time each turn may take, if the turns are timed
+    pub fn turn_limit(&self) -> Option<Duration> {
+        self.turn_limit
+    }
+
+    /// The time left for the current turn, or [`None`] if the turns aren't timed or the game is
+    /// over. Once the time is up this is [`Duration::ZERO`].
+    pub fn time_remaining(&self) -> Option<Duration> {
+        let limit = self.turn_limit?;
+        if self.status() != GameStatus::InProgress {
+            return None;
+        }
+        let elapsed = self.turn_started.map(|start| start.elapsed());
+        Some(limit.saturating_sub(elapsed.unwrap_or_default()))
+    }
+
+    /// Ends the game with the current player losing if their time is up, see
+    /// [`Game::time_remaining`]. Returns whether it did.
+    pub fn forfeit_on_time(&mut self) -> bool {
+        if self.time_remaining() != Some(Duration::ZERO) {
+            return false;
+        }
+        self.timed_out = Some(self.next_player);
+        self.end_early();
+        true
+    }
+
+    /// Starts the clock of the next turn, if the turns are timed
+    fn start_turn(&mut self) {
+        if self.turn_limit.is_some() {
+            self.turn_started = Some(Instant::now());
+        }
+    }
+
+    /// Creates a new 3x3 game like [`Game::new`], but drawing the pieces of [`Player::X`] and
+    /// [`Player::O`] with the given symbols.
+    pub fn with_symbols(x: char, o: char) -> Self {
+        Self {
+            symbols: [x, o],
+            ..Self::new()
+        }
+    }
+
+    /// The symbol the pieces of `player` are drawn with
+    pub fn symbol(&self, player: Player) -> char {
+        match player {
+            Player::X => self.symbols[0],
+            Player::O => self.symbols[1],
+        }
+    }
+
+    /// Replays `moves` from the empty 3x3 board.
+    ///
+    /// # Errors
+    ///
+    /// If a move is illegal, naming its index in `moves`.
+    pub fn from_moves(moves: &[usize]) -> Result<Self> {
+        let mut game = Self::new();
+        for (index, &i) in moves.iter().enumerate() {
+            game.make_move(i)
+                .map_err(|err| anyhow!("Move {i} at index {index} is illegal: {err}"))?;
+        }
+        Ok(game)
+    }
+
+    /// Encodes the board on a single line, row by row from the top, with `X`, `O` and `.` for an
+    /// empty square and the rows separated by `|`, e.g. `X.O|..X|O.X`.
+    pub fn encode(&self) -> String {
+        self.arr_squares
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|square| square.map_or('.', |player| player.to_char()))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+
+    /// Parses a board encoded by [`Game::encode`]. The board is as wide as it has rows and is won
+    /// by completing a full row, column or diagonal. The next player is inferred from the counts of
+    /// pieces, see [`Game::infer_next_player`], and the move history starts out empty.
+    ///
+    /// # Errors
+    ///
+    /// If a character isn't `X`, `O` or `.`, if the board isn't square, or if the position
+    /// couldn't come up in a game, see [`Game::validate`].
+    pub fn decode(s: &str) -> Result<Self> {
+        let rows: Vec<&str> = s.split('|').collect();
+        let n = rows.len();
+        let mut game = Self::with_size(n, n);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != n {
+                return Err(anyhow!(
+                    "Row {} has {} squares instead of {n}",
+                    y + 1,
+                    row.chars().count()
+                ));
+            }
+            for (x, c) in row.chars().enumerate() {
+                game.arr_squares[y][x] = match c {
+                    'X' => Some(Player::X),
+                    'O' => Some(Player::O),
+                    '.' => None,
+                    _ => return Err(anyhow!("Invalid character {c:?} in row {}", y + 1)),
+                };
+            }
+        }
+        game.next_player = Self::infer_next_player(&game.arr_squares)?;
+        game.validate()?;
+        Ok(game)
+    }
+
+    /// Whose turn it is on a board given by its rows, assuming [`Player::X`] moved first:
+    /// [`Player::X`] if both players have as many pieces, [`Player::O`] if X has one more.
+    ///
+    /// # Errors
+    ///
+    /// If the counts of pieces are anything else.
+    pub fn infer_next_player<R: AsRef<[Option<Player>]>>(board: &[R]) -> Result<Player> {
+        let count = |player| {
+            board
+                .iter()
+                .flat_map(AsRef::as_ref)
+                .filter(|&&square| square == Some(player))
+                .count()
+        };
+        let (x, o) = (count(Player::X), count(Player::O));
+        match x.checked_sub(o) {
+            Some(0) => Ok(Player::X),
+            Some(1) => Ok(Player::O),
+            _ => Err(anyhow!(
+                "X has {x} pieces and O {o}, it can't be anyone's turn"
+            )),
+        }
+    }
+
+    /// Builds a 3x3 game from its rows, top to bottom, with `X` and `O` for the pieces of the
+    /// players, in either case, and `' '` or `'.'` for empty squares. `next` is the player to
+    /// move, and the move history starts out empty.
+    ///
+    /// # Errors
+    ///
+    /// If a character isn't one of these, or if the position couldn't come up in a game with
+    /// `next` to move, see [`Game::validate`].
+    pub fn from_grid(rows: [[char; 3]; 3], next: Player) -> Result<Self> {
+        let mut game = Self::new();
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, c) in row.into_iter().enumerate() {
+                game.arr_squares[y][x] = match c {
+                    ' ' | '.' => None,
+                    _ => Some(
+                        Player::from_char(c)
+                            .ok_or_else(|| anyhow!("Invalid character {c:?} in row {}", y + 1))?,
+                    ),
+                };
+            }
+        }
+        game.next_player = next;
+        // Whoever has more pieces moved first, or the player to move if the counts are equal
+        if game.piece_count(next) < game.piece_count(next.next()) {
+            game.first_player = next.next();
+        } else {
+            game.first_player = next;
+        }
+        game.validate()?;
+        Ok(game)
+    }
+
+    /// Starts setting up a game with pieces already on the board
+    pub fn builder() -> GameBuilder {
+        GameBuilder::default()
+    }
+
+    /// The width and height of the board
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Clears the board and the move history and gives the turn to [`Player::X`], or to the
+    /// player chosen with [`Game::set_first_player`], keeping the size, symbols and time limit of
+    /// the game.
+    pub fn reset(&mut self) {
+        self.arr_squares
+            .iter_mut()
+            .flatten()
+            .for_each(|square| *square = None);
+        self.history.clear();
+        self.undone.clear();
+        self.resigned = None;
+        self.timed_out = None;
+        self.next_player = self.first_player;
+        self.start_turn();
+    }
+
+    /// Lets `player` make the first move instead of [`Player::X`].
+    ///
+    /// # Errors
+    ///
+    /// If there already are pieces on the board.
+    pub fn set_first_player(&mut self, player: Player) -> Result<()> {
+        if self.cells().any(|(_, square)| square.is_some()) {
+            return Err(anyhow!(
+                "The first player can only be chosen before any move is made"
+            ));
+        }
+        self.first_player = player;
+        self.next_player = player;
+        Ok(())
+    }
+
+    /// The squares played so far, in order
+    pub fn moves(&self) -> &[usize] {
+        &self.history
+    }
+
+    /// How many moves were played so far, 0 at the start. Taking back a move with [`Game::undo`]
+    /// lowers the count, and pieces the game was set up with, e.g. with [`Game::decode`], don't
+    /// count.
+    pub fn move_number(&self) -> usize {
+        self.history.len()
+    }
+
+    /// The square played last and the player who played it, or [`None`] before the first move
+    pub fn last_move(&self) -> Option<(usize, Player)> {
+        let &i = self.history.last()?;
+        Some((i, self.square(i).ok()??))
+    }
+
+    /// The player variant for the current turn
+    pub fn get_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Fill the desired square with [`Player::X`] or [`Player::O`] depending on the current player, advancing the turn to
+    /// the next player.
+    ///
+    /// # Errors
+    ///
+    /// [`MoveError::OutOfRange`] if the square number is not between 1 and the number of squares
+    /// (inclusive), [`MoveError::Occupied`] if it corresponds to a taken square, or
+    /// [`MoveError::GameOver`] if a player resigned. If an error is returned, it must be guaranteed that the turn was not advanced to the next player.
+    pub fn make_move(&mut self, i: usize) -> Result<(), MoveError> {
+        if self.forfeited().is_some() {
+            return Err(MoveError::GameOver);
+        }
+        let (y, x) = get_coords(i, self.size).map_err(|_| MoveError::OutOfRange(i))?;
+        // Only worth checking for the end observers, which hear of the move that ends the game
+        let was_over = self.observers.watches_end() && self.status() != GameStatus::InProgress;
+        let target = &mut self.arr_squares[y][x];
+
+        match target {
+            None => {
+                let player = *target.insert(self.next_player);
+                self.next_player = player.next();
+                self.history.push(i);
+                self.undone.clear();
+                self.start_turn();
+
+                let mut observers = std::mem::take(&mut self.observers);
+                observers.notify(self, i, player, was_over);
+                self.observers = observers;
+                Result::Ok(())
             }
-            let str = match self.arr_squares[y][x] {
-                Some(player) if Some(i) == self.last_played => {
-                    player.colored_highlighted().to_string()
+            Some(player) => Err(MoveError::Occupied {
+                square: i,
+                by: *player,
+            }),
+        }
+    }
+
+    /// Fills the square in the given row and column, both counted from 1 at the top left, like
+    /// [`Game::make_move`].
+    ///
+    /// # Errors
+    ///
+    /// If the row or column lies outside of the board, or the square is taken.
+    pub fn make_move_rc(&mut self, row: usize, col: usize) -> Result<()> {
+        Ok(self.make_move(square_at(row, col, self.size)?)?)
+    }
+
+    /// Plays the move like [`Game::make_move`] and reports how the game stands afterwards.
+    ///
+    /// # Errors
+    ///
+    /// In the same cases as [`Game::make_move`], in which case the game is left unchanged.
+    pub fn play(&mut self, i: usize) -> Result<MoveOutcome> {
+        self.make_move(i)?;
+        Ok(match self.status() {
+            GameStatus::Won(player)
+            | GameStatus::Resigned { winner: player }
+            | GameStatus::TimedOut { winner: player } => MoveOutcome::Win(player),
+            GameStatus::Drawn => MoveOutcome::Draw,
+            GameStatus::InProgress => MoveOutcome::Continue,
+        })
+    }
+
+    /// Takes back the last move, returning its square and giving the turn back to the player who
+    /// made it.
+    ///
+    /// # Errors
+    ///
+    /// If no move has been made yet, or a player resigned or ran out of time.
+    pub fn undo(&mut self) -> Result<usize> {
+        if self.forfeited().is_some() {
+            return Err(MoveError::GameOver.into());
+        }
+        let i = self
+            .history
+            .pop()
+            .ok_or_else(|| anyhow!("There is no move to undo"))?;
+        let (y, x) = get_coords(i, self.size)?;
+        self.arr_squares[y][x] = None;
+        self.next_player = self.next_player.next();
+        self.undone.push(i);
+        self.start_turn();
+        Ok(i)
+    }
+
+    /// Plays the move taken back last with [`Game::undo`] again, returning its square. Playing
+    /// any other move forgets the moves that were taken back.
+    ///
+    /// # Errors
+    ///
+    /// If no move was taken back since the last move, or the move can't be played, e.g. because a
+    /// player resigned.
+    pub fn redo(&mut self) -> Result<usize> {
+        let i = self
+            .undone
+            .pop()
+            .ok_or_else(|| anyhow!("There is no move to redo"))?;
+        // Keep the remaining moves, which playing the move would forget
+        let mut undone = std::mem::take(&mut self.undone);
+        let result = self.make_move(i);
+        if result.is_err() {
+            undone.push(i);
+        }
+        self.undone = undone;
+        result?;
+        Ok(i)
+    }
+
+    /// The numbers of all empty squares, in ascending order
+    pub fn available_moves(&self) -> Vec<usize> {
+        self.cells()
+            .filter(|(_, square)| square.is_none())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The occupant of square `i`, or [`None`] if it is empty.
+    ///
+    /// # Errors
+    ///
+    /// If the square number is not between 1 and the number of squares (inclusive).
+    pub fn square(&self, i: usize) -> Result<Option<Player>> {
+        let (y, x) = get_coords(i, self.size)?;
+        Ok(self.arr_squares[y][x])
+    }
+
+    /// Whether square `i` is the center, a corner or an edge of the board.
+    ///
+    /// # Errors
+    ///
+    /// If the square number is not between 1 and the number of squares (inclusive).
+    pub fn square_type(&self, i: usize) -> Result<SquareType> {
+        let (y, x) = get_coords(i, self.size)?;
+        let last = self.size - 1;
+        Ok(if [y, x].iter().all(|&c| c == 0 || c == last) {
+            SquareType::Corner
+        } else if self.size % 2 == 1 && y == last / 2 && x == last / 2 {
+            SquareType::Center
+        } else {
+            SquareType::Edge
+        })
+    }
+
+    /// Indicates whether square `i` is on the board and empty, i.e. whether [`Game::make_move`]
+    /// would accept it, without playing it
+    pub fn is_legal(&self, i: usize) -> bool {
+        let n = self.size;
+        self.forfeited().is_none()
+            && (1..=n * n).contains(&i)
+            && self.arr_squares[(i - 1) / n][(i - 1) % n].is_none()
+    }
+
+    /// The empty squares that would complete a line for `player` if they played there, in
+    /// ascending order. More than one means an unstoppable double threat.
+    ///
+    /// These are the moves that win right away, so under [`WinRule::Misere`], where completing a
+    /// line loses, there are none. Neither are there once the game is over.
+    pub fn winning_moves(&self, player: Player) -> Vec<usize> {
+        if self.status() != GameStatus::InProgress {
+            return Vec::new();
+        }
+        if self.win_rule == WinRule::Misere {
+            return Vec::new();
+        }
+        // Runs holding the player's pieces everywhere but an empty square
+        let n = self.size;
+        let mut moves: Vec<usize> = self
+            .runs()
+            .filter_map(|line| {
+                let owned = line
+                    .iter()
+                    .filter(|&&(y, x)| self.arr_squares[y][x] == Some(player))
+                    .count();
+                let empty: Vec<_> = line
+                    .iter()
+                    .filter(|&&(y, x)| self.arr_squares[y][x].is_none())
+                    .collect();
+                match empty[..] {
+                    [&(y, x)] if owned == line.len() - 1 => Some(y * n + x + 1),
+                    _ => None,
+                }
+            })
+            .collect();
+        moves.sort_unstable();
+        moves.dedup();
+        moves
+    }
+
+    /// The empty squares where `player` would create a fork, i.e. leave themselves at least two
+    /// [winning moves](Game::winning_moves) for their next turn, in ascending order. Like winning
+    /// moves, there are none under [`WinRule::Misere`].
+    pub fn fork_moves(&self, player: Player) -> Vec<usize> {
+        self.available_moves()
+            .into_iter()
+            .filter(|&i| {
+                let mut child = self.clone();
+                child.next_player = player;
+                child.make_move(i).is_ok()
+                    && child.get_winner().is_none()
+                    && child.winning_moves(player).len() >= 2
+            })
+            .collect()
+    }
+
+    /// Every square number along with its occupant, row by row from the top left
+    pub fn cells(&self) -> impl Iterator<Item = (usize, Option<Player>)> + '_ {
+        (1..).zip(self.arr_squares.iter().flatten().copied())
+    }
+
+    /// Indicates whether or not the board is full, useful for tie checking
+    pub fn is_full(&self) -> bool {
+        self.empty_count() == 0
+    }
+
+    /// The number of empty squares left
+    pub fn empty_count(&self) -> usize {
+        self.arr_squares
+            .iter()
+            .flatten()
+            .filter(|square| square.is_none())
+            .count()
+    }
+
+    /// The best square for the current player according to a full minimax search, or [`None`] if
+    /// the game is over. Ties are broken in favor of the lowest square.
+    pub fn best_move(&self) -> Option<usize> {
+        ai::rank_moves(self).first().map(|&(i, _)| i)
+    }
+
+    /// Suggests a square to the current player without playing it, see [`Game::best_move`]
+    pub fn hint(&self) -> Option<usize> {
+        self.best_move()
+    }
+
+    /// Whether the current player wins, loses or draws with perfect play from both sides,
+    /// according to a full minimax search
+    pub fn evaluate(&self) -> Eval {
+        ai::evaluate(self)
+    }
+
+    /// A move for the current player at the given difficulty, or [`None`] if the game is over.
+    /// Unlike [`Game::best_move`], [`Difficulty::Hard`] varies its first moves between equally
+    /// good squares.
+    ///
+    /// Random choices come from the thread's random generator, use [`Game::ai_move_with`] to pick
+    /// the generator, e.g. a seeded one for reproducible games.
+    pub fn ai_move(&self, difficulty: Difficulty) -> Option<usize> {
+        self.ai_move_with(difficulty, &mut rand::rng())
+    }
+
+    /// Like [`Game::ai_move`], drawing random choices from `rng`
+    pub fn ai_move_with<R: Rng + ?Sized>(
+        &self,
+        difficulty: Difficulty,
+        rng: &mut R,
+    ) -> Option<usize> {
+        ai::choose_move(self, difficulty, rng)
+    }
+
+    /// Plays a whole game of random legal moves for both sides, from an empty board until a line is
+    /// completed or the board is full, e.g. to fuzz the engine or for win-rate statistics. Returns
+    /// the final game along with its status. [`ai::random_playout`] plays on from a given position
+    /// instead.
+    ///
+    /// ```
+    /// use tictactoe::{Game, GameStatus};
+    ///
+    /// let (game, status) = Game::random_playout(&mut rand::rng());
+    /// assert_ne!(GameStatus::InProgress, status);
+    /// assert_eq!(status, game.status());
+    /// ```
+    pub fn random_playout<R: Rng + ?Sized>(rng: &mut R) -> (Game, GameStatus) {
+        let game = ai::random_playout(&Game::new(), rng);
+        let status = game.status();
+        (game, status)
+    }
+
+    /// A move for the current player picked by [random playouts](ai::random_playout) rather
+    /// than a full search, e.g. for boards too large to search: every legal move is followed by
+    /// `iterations` playouts, and the move winning the most of them is played, counting draws as
+    /// half a win. More iterations play stronger but slower. Returns [`None`] if the game is over.
+    pub fn mcts_move<R: Rng + ?Sized>(&self, iterations: usize, rng: &mut R) -> Option<usize> {
+        ai::monte_carlo_move(self, iterations, rng)
+    }
+
+    /// Returns the winner of the current board or [`None`]. Under [`WinRule::Misere`] this is the
+    /// opponent of the player who completed a line.
+    pub fn get_winner(&self) -> Option<Player> {
+        self.winning_line().map(|(player, _)| player)
+    }
+
+    /// Ends the game with `player` giving up, making the other player the winner. Does nothing if
+    /// the game is already over.
+    pub fn resign(&mut self, player: Player) {
+        if self.status() != GameStatus::InProgress {
+            return;
+        }
+        self.resigned = Some(player);
+        self.end_early();
+    }
+
+    /// The player who lost by resigning or running out of time, if any
+    fn forfeited(&self) -> Option<Player> {
+        self.resigned.or(self.timed_out)
+    }
+
+    /// Stops the clock and tells the end observers after a player forfeited
+    fn end_early(&mut self) {
+        self.turn_started = None;
+
+        let mut observers = std::mem::take(&mut self.observers);
+        observers.notify_end(self);
+        self.observers = observers;
+    }
+
+    /// Returns whether the game is still going, was won, forfeited, or can only end in a draw
+    pub fn status(&self) -> GameStatus {
+        if let Some(player) = self.resigned {
+            return GameStatus::Resigned {
+                winner: player.next(),
+            };
+        }
+        if let Some(player) = self.timed_out {
+            return GameStatus::TimedOut {
+                winner: player.next(),
+            };
+        }
+        match self.get_winner() {
+            Some(player) => GameStatus::Won(player),
+            None if self.is_draw_inevitable() => GameStatus::Drawn,
+            None => GameStatus::InProgress,
+        }
+    }
+
+    /// Describes why the game ended, e.g. which line the winner completed, or [`None`] if the game
+    /// is still going. Players are named by their symbols, without terminal colors.
+    pub fn end_reason(&self) -> Option<String> {
+        if let Some(player) = self.resigned {
+            return Some(format!("{} resigned", self.symbol(player)));
+        }
+        if let Some(player) = self.timed_out {
+            return Some(format!("{} ran out of time", self.symbol(player)));
+        }
+        match self.completed_line() {
+            Some((player, line)) => Some(format!(
+                "{} completed {}{}",
+                self.symbol(player),
+                line_name(&line, self.size, self.win_len),
+                match self.win_rule {
+                    WinRule::Normal => "",
+                    WinRule::Misere => " and loses",
                 }
-                Some(player) => player.to_string(),
-                None => i.to_string(),
+            )),
+            None if self.is_full() => Some("board full — no winner".to_owned()),
+            None if self.is_draw_inevitable() => {
+                Some("no line can be completed anymore".to_owned())
+            }
+            None => None,
+        }
+    }
+
+    /// Returns the winner along with the (y, x) coordinates of the squares forming the completed
+    /// line that decided the game, or [`None`].
+    pub fn winning_line(&self) -> Option<(Player, Vec<(usize, usize)>)> {
+        let (player, line) = self.completed_line()?;
+        match self.win_rule {
+            WinRule::Normal => Some((player, line)),
+            WinRule::Misere => Some((player.next(), line)),
+        }
+    }
+
+    /// The player who completed a line along with its (y, x) coordinates, or [`None`]
+    fn completed_line(&self) -> Option<(Player, Vec<(usize, usize)>)> {
+        [Player::X, Player::O]
+            .into_iter()
+            .find_map(|player| Some((player, self.line_of(player)?)))
+    }
+
+    /// A number identifying the position: the occupant of every square and whose turn it is, but
+    /// not the order the moves were played in, e.g. to cache evaluations of positions reached in
+    /// different ways.
+    ///
+    /// The key is the same across runs and distinct for every position on boards of up to 6x6
+    /// squares. Larger boards may share keys.
+    pub fn position_key(&self) -> u64 {
+        let squares = self.cells().fold(0u64, |key, (_, square)| {
+            let digit = match square {
+                None => 0,
+                Some(Player::X) => 1,
+                Some(Player::O) => 2,
             };
-            write!(f, "| {} ", str)?;
-            if x == 2 {
-                writeln!(f, "|")?;
+            key.wrapping_mul(3).wrapping_add(digit)
+        });
+        let turn = match self.next_player {
+            Player::X => 0,
+            Player::O => 1,
+        };
+        squares.wrapping_mul(2).wrapping_add(turn)
+    }
+
+    /// The squares of the board, row by row, after rotating and reflecting it into the
+    /// lexicographically smallest of its 8 symmetric forms, with empty squares ordering first.
+    /// Positions that only differ by a rotation or reflection have the same canonical form.
+    ///
+    /// The rows are a `Vec` because boards can be any size, see [`Game::canonical_3x3`] for the
+    /// fixed-size form of the standard board.
+    pub fn canonical(&self) -> Vec<Vec<Option<Player>>> {
+        let n = self.size;
+        let rotate = |board: &Board| -> Board {
+            (0..n)
+                .map(|y| (0..n).map(|x| board[n - 1 - x][y]).collect())
+                .collect()
+        };
+        let transposed: Board = (0..n)
+            .map(|y| (0..n).map(|x| self.arr_squares[x][y]).collect())
+            .collect();
+
+        let mut forms = Vec::with_capacity(8);
+        for mut board in [self.arr_squares.clone(), transposed] {
+            for _ in 0..4 {
+                let next = rotate(&board);
+                forms.push(board);
+                board = next;
+            }
+        }
+        // There is always at least the board itself
+        forms.into_iter().min().unwrap_or_default()
+    }
+
+    /// [`Game::canonical`] as an array, or `None` unless the board is 3x3
+    pub fn canonical_3x3(&self) -> Option<[[Option<Player>; 3]; 3]> {
+        let rows: Vec<[Option<Player>; 3]> = self
+            .canonical()
+            .into_iter()
+            .map(|row| row.try_into().ok())
+            .collect::<Option<_>>()?;
+        rows.try_into().ok()
+    }
+
+    /// Indicates whether the game can only end in a draw, because every line already holds pieces
+    /// of both players, even if there are empty squares left
+    pub fn is_draw_inevitable(&self) -> bool {
+        self.runs().all(|line| {
+            let has = |player| {
+                line.iter()
+                    .any(|&(y, x)| self.arr_squares[y][x] == Some(player))
+            };
+            has(Player::X) && has(Player::O)
+        })
+    }
+
+    /// Checks that the position can come up in an actual game: the players took turns starting
+    /// with the first player, so the counts of their pieces match whose turn it is, and at most one
+    /// of them completed a line, with the last move.
+    ///
+    /// # Errors
+    ///
+    /// Describing the first of these rules the position breaks.
+    pub fn validate(&self) -> Result<()> {
+        let (x, o) = (self.piece_count(Player::X), self.piece_count(Player::O));
+        let (first, second) = match self.first_player {
+            Player::X => (x, o),
+            Player::O => (o, x),
+        };
+        let turn_matches = if self.next_player == self.first_player {
+            first == second
+        } else {
+            first == second + 1
+        };
+        if !turn_matches {
+            return Err(anyhow!(
+                "It cannot be {}'s turn with {x} X and {o} O on the board",
+                self.next_player
+            ));
+        }
+
+        match (self.line_of(Player::X), self.line_of(Player::O)) {
+            (Some(_), Some(_)) => Err(anyhow!("Both players completed a line")),
+            (Some(_), None) if self.next_player == Player::X => {
+                Err(anyhow!("O moved after X completed a line"))
             }
+            (None, Some(_)) if self.next_player == Player::O => {
+                Err(anyhow!("X moved after O completed a line"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The number of pieces `player` has on the board
+    pub fn piece_count(&self, player: Player) -> usize {
+        self.arr_squares
+            .iter()
+            .flatten()
+            .filter(|&&square| square == Some(player))
+            .count()
+    }
+
+    /// The square numbers of the 8 lines of three on a standard 3x3 board: rows, then columns and
+    /// the two diagonals. [`Game::winning_moves`], and through it [`Game::fork_moves`], scan them
+    /// on such boards, and the runs of the board's win length on others.
+    pub fn lines() -> &'static [[usize; 3]; 8] {
+        &LINES
+    }
+
+    /// The (y, x) coordinates of the first line completed by `player`
+    fn line_of(&self, player: Player) -> Option<Vec<(usize, usize)>> {
+        self.runs().find(|line| {
+            line.iter()
+                .all(|&(y, x)| self.arr_squares[y][x] == Some(player))
+        })
+    }
+
+    /// The (y, x) coordinates of every run of squares that wins the game when filled by one
+    /// player: rows, then columns, diagonals and anti-diagonals. These are the [`LINES`] on a
+    /// standard board.
+    fn runs(&self) -> Box<dyn Iterator<Item = Vec<(usize, usize)>> + '_> {
+        if (self.size, self.win_len) == (3, 3) {
+            return Box::new(
+                LINES
+                    .iter()
+                    .map(|line| line.iter().map(|i| ((i - 1) / 3, (i - 1) % 3)).collect()),
+            );
+        }
+        Box::new(runs(self.size, self.win_len))
+    }
+}
+
+/// The square numbers of every line on a standard 3x3 board, see [`Game::lines`]
+const LINES: [[usize; 3]; 8] = [
+    [1, 2, 3],
+    [4, 5, 6],
+    [7, 8, 9],
+    [1, 4, 7],
+    [2, 5, 8],
+    [3, 6, 9],
+    [1, 5, 9],
+    [3, 5, 7],
+];
+
+/// The (y, x) coordinates of every straight run of `len` squares on an `n`x`n` board, in the order
+/// of [`Game::runs`]
+fn runs(n: usize, len: usize) -> impl Iterator<Item = Vec<(usize, usize)>> {
+    const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+    let len = len as isize;
+    let at = move |(y, x): (usize, usize), (dy, dx): (isize, isize), step: isize| {
+        let y = usize::try_from(y as isize + dy * step)
+            .ok()
+            .filter(|&y| y < n)?;
+        let x = usize::try_from(x as isize + dx * step)
+            .ok()
+            .filter(|&x| x < n)?;
+        Some((y, x))
+    };
+
+    DIRECTIONS.into_iter().flat_map(move |direction| {
+        (0..n * n).filter_map(move |start| {
+            let start = (start / n, start % n);
+            // The line is straight, so it fits on the board if both of its ends do
+            at(start, direction, len - 1)?;
+            Some(
+                (0..len)
+                    .filter_map(|step| at(start, direction, step))
+                    .collect(),
+            )
+        })
+    })
+}
+
+/// Names a line of the standard board, like "the top row", or describes a run of a board of
+/// another size by where it starts, like "row 2 from square 6"
+fn line_name(line: &[(usize, usize)], size: usize, win_len: usize) -> String {
+    const ROWS: [&str; 3] = ["the top row", "the middle row", "the bottom row"];
+    const COLUMNS: [&str; 3] = ["the left column", "the middle column", "the right column"];
+
+    let ((y0, x0), (y1, x1)) = (line[0], line[line.len() - 1]);
+    if (size, win_len) == (3, 3) {
+        return if y0 == y1 {
+            ROWS[y0].to_owned()
+        } else if x0 == x1 {
+            COLUMNS[x0].to_owned()
+        } else if x0 < x1 {
+            "the diagonal from the top left".to_owned()
+        } else {
+            "the diagonal from the top right".to_owned()
+        };
+    }
+    let start = y0 * size + x0 + 1;
+    if y0 == y1 {
+        format!("row {} from square {start}", y0 + 1)
+    } else if x0 == x1 {
+        format!("column {} from square {start}", x0 + 1)
+    } else if x0 < x1 {
+        format!("the diagonal down to the right from square {start}")
+    } else {
+        format!("the diagonal down to the left from square {start}")
+    }
+}
+
+/// The corner diagonally across the standard 3x3 board from corner `i`, e.g. 1 for 9.
+///
+/// # Errors
+///
+/// If `i` is out of range or not a corner.
+pub fn opposite_corner(i: usize) -> Result<usize> {
+    match get_coords(i, 3)? {
+        (0 | 2, 0 | 2) => Ok(10 - i),
+        _ => Err(anyhow!("Square {i} is not a corner")),
+    }
+}
+
+/// Parses an algebraic coordinate like `b2` into its square number on a `size`x`size` board. The
+/// letter picks the column from the left and the number the row from the top, so `a1` is
+/// square 1 and `c3` square 9 on the standard board.
+///
+/// # Errors
+///
+/// If the input is not a column letter followed by a row number, or lies outside of the board.
+pub fn parse_algebraic(s: &str, size: usize) -> Result<usize> {
+    let out_of_range = || {
+        anyhow!(
+            "Input must be a coordinate between a1 and {}",
+            to_algebraic(size * size, size).unwrap_or_default()
+        )
+    };
+    let mut chars = s.chars();
+    let col = chars
+        .next()
+        .filter(char::is_ascii_lowercase)
+        .ok_or_else(out_of_range)?;
+    let col = col as usize - 'a' as usize;
+    let row = chars
+        .as_str()
+        .parse::<usize>()
+        .map_err(|_| out_of_range())?;
+    if col >= size || !(1..=size).contains(&row) {
+        return Err(out_of_range());
+    }
+    Ok((row - 1) * size + col + 1)
+}
+
+/// The number of the square in the given row and column of a `size`x`size` board, both counted
+/// from 1 at the top left, see [`Game::make_move_rc`].
+///
+/// # Errors
+///
+/// If the row or column lies outside of the board.
+pub fn square_at(row: usize, col: usize, size: usize) -> Result<usize> {
+    if !(1..=size).contains(&row) || !(1..=size).contains(&col) {
+        return Err(anyhow!("Row and column must be between 1 and {size}"));
+    }
+    Ok((row - 1) * size + col)
+}
+
+/// The algebraic coordinate of square `i` on a `size`x`size` board, the inverse of
+/// [`parse_algebraic`]
+pub fn to_algebraic(i: usize, size: usize) -> Result<String> {
+    let (y, x) = get_coords(i, size)?;
+    let col = u8::try_from(x)
+        .ok()
+        .and_then(|x| b'a'.checked_add(x))
+        .filter(u8::is_ascii_lowercase)
+        .ok_or_else(|| anyhow!("The board is too wide for algebraic coordinates"))?;
+    Ok(format!("{}{}", char::from(col), y + 1))
+}
+
+/// The (y, x) coordinates of square `i` on a board `size` squares wide and high
+fn get_coords(i: usize, size: usize) -> Result<(usize, usize)> {
+    if !(1..=size * size).contains(&i) {
+        return Err(anyhow!("Input must be between 1 and {}", size * size));
+    }
+    let i = i - 1;
+
+    Ok((i / size, i % size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Player::*;
+
+    #[test]
+    fn new_game() {
+        let game = Game::new();
+        for i in game.arr_squares.into_iter().flatten() {
+            assert_eq!(None, i);
+        }
+        assert_eq!(game.next_player, Player::X);
+    }
+
+    #[test]
+    fn get_player_test() -> Result<()> {
+        let mut game = Game::new();
+        assert_eq!(X, game.get_player());
+        game.make_move(5)?;
+        assert_eq!(O, game.get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn make_valid_move() -> Result<()> {
+        let mut game = Game::new();
+        game.make_move(5)?;
+        assert_eq!(Some(X), game.arr_squares[1][1]);
+        Ok(())
+    }
+
+    #[test]
+    fn make_out_of_range_move() {
+        let mut game = Game::new();
+        assert_eq!(Err(MoveError::OutOfRange(99)), game.make_move(99));
+        assert_eq!(Err(MoveError::OutOfRange(0)), game.make_move(0));
+        assert_eq!(X, game.get_player());
+    }
+
+    #[test]
+    fn make_invalid_move() -> Result<()> {
+        let mut game = Game::new();
+        game.make_move(5)?;
+        match game.make_move(5) {
+            Err(MoveError::Occupied { square: 5, by: X }) => {}
+            other => panic!("Expected square 5 to be occupied by X, got {other:?}"),
+        }
+        assert_eq!(O, game.get_player());
+        assert_eq!(
+            format!("Tile 5 is already filled by {X}"),
+            MoveError::Occupied { square: 5, by: X }.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn row_column_corners() -> Result<()> {
+        let mut game = Game::new();
+        game.make_move_rc(1, 1)?;
+        game.make_move_rc(3, 3)?;
+        assert_eq!(&[1, 9], game.moves());
+        Ok(())
+    }
+
+    #[test]
+    fn row_column_out_of_range() {
+        let mut game = Game::new();
+        for (row, col) in [(0, 1), (1, 0), (4, 1), (1, 4)] {
+            let err = game.make_move_rc(row, col).unwrap_err();
+            assert_eq!("Row and column must be between 1 and 3", err.to_string());
+        }
+        assert_eq!(X, game.get_player());
+    }
+
+    #[test]
+    fn play_continue() -> Result<()> {
+        let mut game = Game::new();
+        assert_eq!(MoveOutcome::Continue, game.play(5)?);
+        assert!(game.play(5).is_err());
+        assert_eq!(O, game.get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn play_win() -> Result<()> {
+        let mut game = Game::new();
+        for i in [1, 4, 2, 5] {
+            game.play(i)?;
+        }
+        assert_eq!(MoveOutcome::Win(X), game.play(3)?);
+        Ok(())
+    }
+
+    #[test]
+    fn play_draw() -> Result<()> {
+        let mut game = Game::new();
+        for i in [1, 2, 3, 5, 8, 4, 6] {
+            assert_eq!(MoveOutcome::Continue, game.play(i)?);
+        }
+        assert_eq!(MoveOutcome::Draw, game.play(9)?);
+        assert_eq!(MoveOutcome::Draw, game.play(7)?);
+
+        // Drawn before the board is full
+        let mut game = Game::new();
+        for i in [1, 2, 4, 5, 8, 7, 3] {
+            assert_eq!(MoveOutcome::Continue, game.play(i)?);
+        }
+        assert_eq!(MoveOutcome::Draw, game.play(6)?);
+        assert_eq!(GameStatus::Drawn, game.status());
+        Ok(())
+    }
+
+    #[test]
+    fn replay_moves() -> Result<()> {
+        let moves = [5, 1, 3, 7, 9, 4];
+        let game = Game::from_moves(&moves)?;
+        assert_eq!(Some(O), game.get_winner());
+        assert_eq!(X, game.get_player());
+        assert_eq!(&moves, game.moves());
+        Ok(())
+    }
+
+    #[test]
+    fn replay_repeated_square() {
+        let err = Game::from_moves(&[5, 1, 5, 9]).unwrap_err();
+        assert!(err.to_string().contains("Move 5 at index 2"));
+    }
+
+    #[test]
+    fn last_move() -> Result<()> {
+        let mut game = Game::new();
+        assert_eq!(None, game.last_move());
+        game.make_move(5)?;
+        game.make_move(1)?;
+        assert_eq!(Some((1, O)), game.last_move());
+        game.undo()?;
+        assert_eq!(Some((5, X)), game.last_move());
+        Ok(())
+    }
+
+    #[test]
+    fn undo_moves() -> Result<()> {
+        let mut game = Game::new();
+        for i in [5, 1, 9] {
+            game.make_move(i)?;
+        }
+        assert_eq!(9, game.undo()?);
+        assert_eq!(1, game.undo()?);
+        assert_eq!(O, game.get_player());
+        assert_eq!(vec![5], game.history);
+        assert_eq!(
+            vec![
+                vec![None, None, None],
+                vec![None, Some(X), None],
+                vec![None, None, None],
+            ],
+            game.arr_squares
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn redo_moves() -> Result<()> {
+        let mut game = Game::new();
+        for i in [5, 1, 9] {
+            game.make_move(i)?;
+        }
+        game.undo()?;
+        game.undo()?;
+        assert_eq!(1, game.redo()?);
+        assert_eq!(Some(O), game.square(1)?);
+        assert_eq!(None, game.square(9)?);
+        assert_eq!(X, game.get_player());
+
+        game.make_move(3)?;
+        assert!(game.redo().is_err());
+        assert_eq!(&[5, 1, 3], game.moves());
+        Ok(())
+    }
+
+    #[test]
+    fn redo_without_undo() {
+        let mut game = Game::new();
+        assert!(game.redo().is_err());
+        assert_eq!(X, game.get_player());
+    }
+
+    #[test]
+    fn undo_without_moves() {
+        let mut game = Game::new();
+        assert!(game.undo().is_err());
+        assert_eq!(X, game.get_player());
+    }
+
+    #[test]
+    fn move_numbers() -> Result<()> {
+        let mut game = Game::new();
+        assert_eq!(0, game.move_number());
+        for i in [5, 1, 9] {
+            game.make_move(i)?;
+        }
+        assert_eq!(3, game.move_number());
+        game.undo()?;
+        assert_eq!(2, game.move_number());
+        assert_eq!(0, Game::decode("X..|.O.|...")?.move_number());
+        Ok(())
+    }
+
+    #[test]
+    fn reset_game() -> Result<()> {
+        let mut game = Game::from_moves(&[5, 1, 9])?;
+        game.reset();
+        assert!(game.arr_squares.iter().flatten().all(Option::is_none));
+        assert_eq!(X, game.get_player());
+        assert!(game.moves().is_empty());
+        assert_eq!(Game::new(), game);
+        Ok(())
+    }
+
+    #[test]
+    fn o_moves_first() -> Result<()> {
+        let mut game = Game::new();
+        game.set_first_player(O)?;
+        assert_eq!(O, game.get_player());
+        game.make_move(5)?;
+        assert_eq!(Some(O), game.square(5)?);
+        assert_eq!(X, game.get_player());
+        game.validate()?;
+
+        game.reset();
+        assert_eq!(O, game.get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn first_player_after_moves() -> Result<()> {
+        let mut game = Game::from_moves(&[5])?;
+        assert!(game.set_first_player(O).is_err());
+        assert_eq!(O, game.get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn available_moves_empty_board() {
+        let game = Game::new();
+        assert_eq!((1..=9).collect::<Vec<_>>(), game.available_moves());
+    }
+
+    #[test]
+    fn available_moves_partial_board() -> Result<()> {
+        let game = Game::from_moves(&[5, 1, 9])?;
+        assert_eq!(vec![2, 3, 4, 6, 7, 8], game.available_moves());
+        Ok(())
+    }
+
+    #[test]
+    fn available_moves_full_board() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), Some(X)],
+            vec![Some(O), Some(O), Some(X)],
+            vec![Some(O), Some(X), Some(X)],
+        ];
+        assert!(game.available_moves().is_empty());
+    }
+
+    #[test]
+    fn square_contents() -> Result<()> {
+        let mut game = Game::new();
+        assert_eq!(None, game.square(5)?);
+        game.make_move(5)?;
+        game.make_move(9)?;
+        assert_eq!(Some(X), game.square(5)?);
+        assert_eq!(Some(O), game.square(9)?);
+        assert!(game.square(0).is_err());
+        assert!(game.square(10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn encoding() -> Result<()> {
+        assert_eq!("...|...|...", Game::new().encode());
+        let game = Game::from_moves(&[1, 3, 6, 7, 9])?;
+        assert_eq!("X.O|..X|O.X", game.encode());
+
+        let decoded = Game::decode("X.O|..X|O.X")?;
+        assert_eq!(game.arr_squares, decoded.arr_squares);
+        assert_eq!(O, decoded.get_player());
+        assert_eq!("X.O|..X|O.X", decoded.encode());
+        assert_eq!(X, Game::decode("X..|.O.|...")?.get_player());
+
+        let larger = Game::decode("X...|.O..|....|...X")?;
+        assert_eq!(4, larger.size());
+        assert_eq!("X...|.O..|....|...X", larger.encode());
+        Ok(())
+    }
+
+    #[test]
+    fn inferred_next_player() -> Result<()> {
+        let mut board = [[None; 3]; 3];
+        assert_eq!(X, Game::infer_next_player(&board)?);
+        board[1][1] = Some(X);
+        assert_eq!(O, Game::infer_next_player(&board)?);
+        board[0][0] = Some(O);
+        assert_eq!(X, Game::infer_next_player(&board)?);
+
+        board[0][0] = Some(X);
+        let err = Game::infer_next_player(&board).unwrap_err();
+        assert_eq!(
+            "X has 2 pieces and O 0, it can't be anyone's turn",
+            err.to_string()
+        );
+        board[0][0] = None;
+        board[1][1] = Some(O);
+        assert!(Game::infer_next_player(&board).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn grid_positions() -> Result<()> {
+        let game = Game::from_grid([['X', ' ', '.'], [' ', 'o', ' '], ['.', '.', 'x']], O)?;
+        assert_eq!(Game::decode("X..|.O.|..X")?, game);
+
+        let game = Game::from_grid([['.', '.', '.'], ['.', 'O', '.'], ['.', '.', '.']], X)?;
+        assert_eq!(O, game.first_player);
+        assert_eq!(Some(O), game.square(5)?);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_grids() {
+        let empty = [['.'; 3]; 3];
+        let mut grid = empty;
+        grid[1][2] = '#';
+        let err = Game::from_grid(grid, X).unwrap_err();
+        assert_eq!("Invalid character '#' in row 2", err.to_string());
+
+        // X can't have two more pieces than O
+        let mut grid = empty;
+        grid[0] = ['X', 'X', '.'];
+        assert!(Game::from_grid(grid, O).is_err());
+    }
+
+    #[test]
+    fn malformed_encoding() {
+        for s in [
+            "",
+            "X.O|..X",
+            "X.O|..X|O.",
+            "X.O|..X|O.XX",
+            "X.O|..Y|O.X",
+            "XXX|OOO|...",
+        ] {
+            assert!(Game::decode(s).is_err(), "{s:?} should not decode");
+        }
+        // More O than X
+        assert!(Game::decode("O..|...|...").is_err());
+    }
+
+    #[test]
+    fn single_winning_move() -> Result<()> {
+        let game = Game::from_moves(&[1, 4, 2])?;
+        assert_eq!(vec![3], game.winning_moves(X));
+        assert!(game.winning_moves(O).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn fork_winning_moves() -> Result<()> {
+        // X threatens both the top row and the left column
+        let game = Game::from_moves(&[1, 5, 3, 8, 7])?;
+        assert_eq!(vec![2, 4], game.winning_moves(X));
+        Ok(())
+    }
+
+    #[test]
+    fn no_winning_moves_when_over() -> Result<()> {
+        let game = Game::from_moves(&[1, 4, 2, 5, 3])?;
+        assert!(game.winning_moves(X).is_empty());
+        assert!(game.winning_moves(O).is_empty());
+
+        let mut game = Game::with_size(4, 3);
+        for i in [1, 5, 2, 6, 3] {
+            game.make_move(i)?;
         }
-        write!(f, "|___|___|___|")
+        assert_eq!(Some(X), game.get_winner());
+        assert!(game.winning_moves(X).is_empty());
+        Ok(())
     }
-}
 
-impl Game {
-    /// Creates a new game with an empty board and next player set to [`Player::X`]
-    pub fn new() -> Self {
-        let row = [None; 3];
-        let arr_squares = [row; 3];
+    #[test]
+    fn no_winning_moves() -> Result<()> {
+        assert!(Game::new().winning_moves(X).is_empty());
+        assert!(Game::from_moves(&[5, 1])?.winning_moves(X).is_empty());
+        Ok(())
+    }
 
-        Self {
-            next_player: Player::X,
-            arr_squares,
-            last_played: None,
+    #[test]
+    fn corner_fork() -> Result<()> {
+        // X holds opposite corners and takes a third one, threatening the left column and the
+        // bottom row at once
+        let game = Game::from_moves(&[1, 2, 9, 5])?;
+        assert_eq!(vec![7], game.fork_moves(X));
+        assert!(Game::new().fork_moves(X).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn square_types() -> Result<()> {
+        let game = Game::new();
+        assert_eq!(SquareType::Center, game.square_type(5)?);
+        for i in [1, 3, 7, 9] {
+            assert_eq!(SquareType::Corner, game.square_type(i)?);
         }
+        for i in [2, 4, 6, 8] {
+            assert_eq!(SquareType::Edge, game.square_type(i)?);
+        }
+        assert!(game.square_type(0).is_err());
+        assert!(game.square_type(10).is_err());
+
+        // Even boards have no center
+        let game = Game::with_size(4, 3);
+        assert_eq!(SquareType::Corner, game.square_type(16)?);
+        assert_eq!(SquareType::Edge, game.square_type(6)?);
+        Ok(())
     }
 
-    /// The player variant for the current turn
-    pub fn get_player(&self) -> Player {
-        self.next_player
+    #[test]
+    fn legal_moves() -> Result<()> {
+        let game = Game::from_moves(&[5])?;
+        assert!(game.is_legal(1));
+        assert!(game.is_legal(9));
+        assert!(!game.is_legal(5));
+        assert!(!game.is_legal(0));
+        assert!(!game.is_legal(10));
+        assert!(Game::with_size(4, 3).is_legal(16));
+        Ok(())
     }
 
-    /// Fill the desired square with [`Player::X`] or [`Player::O`] depending on the current player, advancing the turn to
-    /// the next player.
-    ///
-    /// # Errors
-    ///
-    /// If the square number is not between 1 and 9 (inclusive), or corresponds to a taken square, an
-    /// error variant is returned. If an error is returned, it must be guaranteed that the turn was
-    /// not advanced to the next player.
-    pub fn make_move(&mut self, i: usize) -> Result<()> {
-        let (y, x) = get_coords(i)?;
-        let target = &mut self.arr_squares[y][x];
+    #[test]
+    fn board_cells() -> Result<()> {
+        let game = Game::from_moves(&[5, 1, 9])?;
+        assert_eq!(
+            vec![
+                (1, Some(O)),
+                (2, None),
+                (3, None),
+                (4, None),
+                (5, Some(X)),
+                (6, None),
+                (7, None),
+                (8, None),
+                (9, Some(X)),
+            ],
+            game.cells().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
 
-        match target {
-            None => {
-                let current_player = target.insert(self.next_player);
-                self.next_player = current_player.next();
-                self.last_played = Some(i);
-                Ok(())
-            }
-            Some(player) => Err(anyhow!(format!(
-                "Tile {i} already is already filled by {}",
-                player
-            ))),
-        }
+    #[test]
+    fn is_full() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), Some(X)],
+            vec![Some(O), Some(O), Some(X)],
+            vec![Some(O), Some(X), Some(X)],
+        ];
+        assert!(game.is_full());
     }
 
-    /// Indicates whether or not the board is full, useful for tie checking
-    pub fn is_full(&self) -> bool {
-        self.arr_squares
-            .iter()
-            .flatten()
-            .all(|square| square.is_some())
+    #[test]
+    fn is_not_full() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), Some(X)],
+            vec![Some(O), None, Some(X)],
+            vec![Some(O), Some(X), Some(X)],
+        ];
+        assert!(!game.is_full());
     }
 
-    /// Returns the winner of the current board or [`None`].
-    pub fn get_winner(&self) -> Option<Player> {
-        for player in [Some(Player::X), Some(Player::O)] {
-            for row in 0..3 {
-                if self.arr_squares[row].iter().all(|square| square == &player) {
-                    return player;
-                }
-            }
+    #[test]
+    fn empty_squares() -> Result<()> {
+        assert_eq!(9, Game::new().empty_count());
+        assert_eq!(6, Game::from_moves(&[5, 1, 9])?.empty_count());
+        let full = Game::from_moves(&[1, 2, 3, 5, 8, 4, 6, 9, 7])?;
+        assert_eq!(0, full.empty_count());
+        assert!(full.is_full());
+        Ok(())
+    }
 
-            for col in 0..3 {
-                if self
-                    .arr_squares
-                    .iter()
-                    .map(|row| row[col])
-                    .all(|square| square == player)
-                {
-                    return player;
-                }
-            }
+    #[test]
+    fn piece_counts() -> Result<()> {
+        let game = Game::from_moves(&[5, 1, 9, 3, 2])?;
+        assert_eq!(3, game.piece_count(X));
+        assert_eq!(2, game.piece_count(O));
+        assert_eq!(
+            9 - game.empty_count(),
+            game.piece_count(X) + game.piece_count(O)
+        );
+        assert_eq!(0, Game::new().piece_count(X));
+        Ok(())
+    }
 
-            if (
-                self.arr_squares[0][0],
-                self.arr_squares[1][1],
-                self.arr_squares[2][2],
-            ) == (player, player, player)
-            {
-                return player;
-            }
+    #[test]
+    fn draw_checking() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), Some(O)],
+            vec![Some(O), Some(X), Some(X)],
+            vec![Some(O), Some(X), Some(O)],
+        ];
+        assert_eq!(None, game.get_winner());
+    }
+
+    #[test]
+    fn loser_checking() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), Some(O)],
+            vec![None, Some(O), Some(X)],
+            vec![Some(O), Some(O), Some(X)],
+        ];
+        assert_eq!(Some(O), game.get_winner());
+    }
+
+    #[test]
+    fn winner_checking() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), Some(X)],
+            vec![Some(O), Some(O), Some(X)],
+            vec![Some(O), Some(X), Some(X)],
+        ];
+        assert_eq!(Some(X), game.get_winner());
+    }
+
+    #[test]
+    fn diagonal_winning_line() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), None],
+            vec![Some(O), Some(X), None],
+            vec![None, None, Some(X)],
+        ];
+        assert_eq!(Some((X, vec![(0, 0), (1, 1), (2, 2)])), game.winning_line());
+    }
 
-            if (
-                self.arr_squares[0][2],
-                self.arr_squares[1][1],
-                self.arr_squares[2][0],
-            ) == (player, player, player)
-            {
-                return player;
+    #[test]
+    fn winning_moves_match_search() -> Result<()> {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        // Compares the scan of the runs with playing every empty square
+        let played = |game: &Game, player| -> Vec<usize> {
+            game.available_moves()
+                .into_iter()
+                .filter(|&i| {
+                    let mut child = game.clone();
+                    child.next_player = player;
+                    child.make_move(i).is_ok() && child.get_winner() == Some(player)
+                })
+                .collect()
+        };
+        let mut rng = StdRng::seed_from_u64(5);
+        for start in [Game::new(), Game::with_size(4, 3)].iter().cycle().take(60) {
+            let end = ai::random_playout(start, &mut rng);
+            let mut game = start.clone();
+            for &i in end.moves() {
+                if game.get_winner().is_some() {
+                    break;
+                }
+                for player in [X, O] {
+                    assert_eq!(played(&game, player), game.winning_moves(player));
+                }
+                game.make_move(i)?;
             }
         }
+        Ok(())
+    }
 
-        None
+    #[test]
+    fn every_line_wins() {
+        for line in Game::lines() {
+            for player in [X, O] {
+                let mut game = Game::new();
+                for &i in line {
+                    let (y, x) = get_coords(i, 3).unwrap();
+                    game.arr_squares[y][x] = Some(player);
+                }
+                assert_eq!(Some(player), game.get_winner(), "{line:?}");
+            }
+        }
+        // The standard board takes its lines from the table
+        let runs: Vec<_> = runs(3, 3).collect();
+        assert_eq!(runs, Game::new().runs().collect::<Vec<_>>());
     }
-}
 
-fn get_coords(i: usize) -> Result<(usize, usize)> {
-    if i < 1 || i > 9 {
-        return Err(anyhow!("Input must be between 1 and 9".to_owned()));
+    #[test]
+    fn no_winning_line() {
+        let game = Game::new();
+        assert_eq!(None, game.winning_line());
     }
-    let i = i - 1;
 
-    Ok((i / 3, i % 3))
-}
+    #[test]
+    fn inevitable_draw() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), Some(X)],
+            vec![Some(X), Some(O), Some(O)],
+            vec![Some(O), Some(X), None],
+        ];
+        assert!(game.is_draw_inevitable());
+        assert_eq!(
+            Some("no line can be completed anymore".to_owned()),
+            game.end_reason()
+        );
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use Player::*;
+    #[test]
+    fn draw_not_inevitable() -> Result<()> {
+        assert!(!Game::new().is_draw_inevitable());
+        assert!(!Game::from_moves(&[5, 1])?.is_draw_inevitable());
+        assert!(!Game::from_moves(&[1, 4, 2, 5, 3])?.is_draw_inevitable());
+        Ok(())
+    }
 
     #[test]
-    fn display_game() -> Result<()> {
-        let mut game = Game::new();
+    fn game_status() -> Result<()> {
+        assert_eq!(GameStatus::InProgress, Game::new().status());
+        assert_eq!(GameStatus::InProgress, Game::from_moves(&[5, 1])?.status());
         assert_eq!(
-            " ___________
-|   |   |   |
-| 1 | 2 | 3 |
-|___|___|___|
-|   |   |   |
-| 4 | 5 | 6 |
-|___|___|___|
-|   |   |   |
-| 7 | 8 | 9 |
-|___|___|___|"
-                .to_owned(),
-            game.to_string()
+            GameStatus::Won(X),
+            Game::from_moves(&[1, 4, 2, 5, 3])?.status()
         );
-        game.make_move(1)?;
         assert_eq!(
-            format!(
-                " ___________
-|   |   |   |
-| {} | 2 | 3 |
-|___|___|___|
-|   |   |   |
-| 4 | 5 | 6 |
-|___|___|___|
-|   |   |   |
-| 7 | 8 | 9 |
-|___|___|___|",
-                X.colored_highlighted()
-            ),
-            game.to_string()
+            GameStatus::Won(O),
+            Game::from_moves(&[5, 1, 3, 7, 9, 4])?.status()
         );
-        game.make_move(2)?;
         assert_eq!(
-            format!(
-                " ___________
-|   |   |   |
-| {} | {} | 3 |
-|___|___|___|
-|   |   |   |
-| 4 | 5 | 6 |
-|___|___|___|
-|   |   |   |
-| 7 | 8 | 9 |
-|___|___|___|",
-                X.colored(),
-                O.colored_highlighted()
-            ),
-            game.to_string()
+            GameStatus::Drawn,
+            Game::from_moves(&[1, 2, 3, 5, 8, 4, 6, 9, 7])?.status()
         );
+
+        // Drawn before the board is full
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), Some(X)],
+            vec![Some(X), Some(O), Some(O)],
+            vec![Some(O), Some(X), None],
+        ];
+        assert_eq!(GameStatus::Drawn, game.status());
         Ok(())
     }
 
     #[test]
-    fn new_game() {
-        let game = Game::new();
-        for i in game.arr_squares.into_iter().flatten() {
-            assert_eq!(None, i);
-        }
-        assert_eq!(game.next_player, Player::X);
+    fn resignation() -> Result<()> {
+        let mut game = Game::from_moves(&[5, 1])?;
+        game.resign(X);
+        assert_eq!(GameStatus::Resigned { winner: O }, game.status());
+        assert_eq!(Some("X resigned".to_owned()), game.end_reason());
+        assert_eq!(Err(MoveError::GameOver), game.make_move(9));
+        assert!(!game.is_legal(9));
+        assert_eq!(None, game.best_move());
+        assert!(game.undo().is_err());
+        assert_eq!(&[5, 1], game.moves());
+
+        // A move taken back before resigning stays taken back
+        let mut game = Game::from_moves(&[5, 1])?;
+        game.undo()?;
+        game.resign(O);
+        assert!(game.redo().is_err());
+        assert_eq!(vec![1], game.undone);
+
+        // A finished game can't be resigned anymore
+        let mut game = Game::from_moves(&[1, 4, 2, 5, 3])?;
+        game.resign(X);
+        assert_eq!(GameStatus::Won(X), game.status());
+
+        game.reset();
+        game.make_move(5)?;
+        Ok(())
     }
 
     #[test]
-    fn get_player_test() -> Result<()> {
-        let mut game = Game::new();
-        assert_eq!(X, game.get_player());
+    fn turn_clock() -> Result<()> {
+        assert_eq!(None, Game::new().time_remaining());
+
+        let limit = Duration::from_secs(60);
+        let mut game = Game::new().with_turn_limit(limit);
+        assert_eq!(Some(limit), game.turn_limit());
+        assert!(game.time_remaining().is_some_and(|left| left <= limit));
         game.make_move(5)?;
-        assert_eq!(O, game.get_player());
+        assert!(game
+            .time_remaining()
+            .is_some_and(|left| left > Duration::ZERO));
+
+        let mut game = Game::new().with_turn_limit(Duration::ZERO);
+        assert_eq!(Some(Duration::ZERO), game.time_remaining());
+        assert!(game.forfeit_on_time());
+        assert_eq!(GameStatus::TimedOut { winner: O }, game.status());
+        assert_eq!(Some("X ran out of time".to_owned()), game.end_reason());
+        assert_eq!(Err(MoveError::GameOver), game.make_move(5));
+        // The clock stops with the game
+        assert_eq!(None, game.time_remaining());
+
+        let mut game = Game::new().with_turn_limit(limit);
+        assert!(!game.forfeit_on_time());
+        game.resign(X);
+        assert_eq!(None, game.time_remaining());
+        assert!(!game.forfeit_on_time());
         Ok(())
     }
 
     #[test]
-    fn make_valid_move() -> Result<()> {
-        let mut game = Game::new();
-        game.make_move(5)?;
-        assert_eq!(Some(X), game.arr_squares[1][1]);
+    fn transposed_position_keys() -> Result<()> {
+        let a = Game::from_moves(&[5, 1, 9, 3])?;
+        let b = Game::from_moves(&[9, 3, 5, 1])?;
+        assert_ne!(a, b);
+        assert_eq!(a.position_key(), b.position_key());
         Ok(())
     }
 
     #[test]
-    fn make_out_of_range_move() {
+    fn distinct_position_keys() -> Result<()> {
+        let a = Game::from_moves(&[5, 1])?;
+        assert_ne!(a.position_key(), Game::from_moves(&[5, 3])?.position_key());
+        assert_ne!(a.position_key(), Game::from_moves(&[1, 5])?.position_key());
+        assert_ne!(a.position_key(), Game::new().position_key());
+
+        // The same pieces with the other player to move
+        let mut other_turn = a.clone();
+        other_turn.next_player = O;
+        assert_ne!(a.position_key(), other_turn.position_key());
+        Ok(())
+    }
+
+    #[test]
+    fn rotated_boards_are_equivalent() -> Result<()> {
+        let game = Game::from_moves(&[1, 2, 5])?;
+        // The same position turned a quarter clockwise
+        let rotated = Game::from_moves(&[3, 6, 5])?;
+        assert_ne!(game.arr_squares, rotated.arr_squares);
+        assert_eq!(game.canonical(), rotated.canonical());
+        assert_ne!(game.canonical(), Game::from_moves(&[1, 5, 2])?.canonical());
+        Ok(())
+    }
+
+    #[test]
+    fn minimal_board_is_canonical() -> Result<()> {
+        let minimal = Game::from_moves(&[9, 8])?;
+        assert_eq!(minimal.arr_squares, minimal.canonical());
+
+        let game = Game::from_moves(&[1, 2])?;
+        assert_ne!(game.arr_squares, game.canonical());
+        assert_eq!(minimal.arr_squares, game.canonical());
+
+        assert_eq!(Game::new().arr_squares, Game::new().canonical());
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_size_canonical_form() -> Result<()> {
+        let game = Game::from_moves(&[1, 2])?;
+        assert_eq!(
+            game.canonical_3x3(),
+            Some([[None; 3], [None; 3], [None, Some(O), Some(X)]])
+        );
+        assert_eq!(Game::with_size(4, 3).canonical_3x3(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn misere_rule() -> Result<()> {
+        let moves = [1, 4, 2, 5, 3];
+        let normal = Game::from_moves(&moves)?;
+        assert_eq!(Some(X), normal.get_winner());
+        assert_eq!(GameStatus::Won(X), normal.status());
+
+        let mut misere = Game::builder().win_rule(WinRule::Misere).build()?;
+        for i in moves {
+            misere.make_move(i)?;
+        }
+        assert_eq!(WinRule::Misere, misere.win_rule());
+        assert_eq!(Some(O), misere.get_winner());
+        assert_eq!(GameStatus::Won(O), misere.status());
+        assert_eq!(
+            Some("X completed the top row and loses".to_owned()),
+            misere.end_reason()
+        );
+        misere.validate()
+    }
+
+    #[test]
+    fn valid_mid_game() -> Result<()> {
+        Game::from_moves(&[5, 1, 9])?.validate()?;
+        Game::from_moves(&[1, 4, 2, 5, 3])?.validate()?;
+        Game::new().validate()
+    }
+
+    #[test]
+    fn impossible_count() {
         let mut game = Game::new();
-        assert!(game.make_move(99).is_err());
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![None, Some(X), None],
+            vec![None, None, Some(X)],
+        ];
+        game.next_player = O;
+        let err = game.validate().unwrap_err();
+        assert!(err.to_string().contains("with 4 X and 0 O"));
     }
 
     #[test]
-    fn make_invalid_move() -> Result<()> {
+    fn double_winner() {
         let mut game = Game::new();
-        game.make_move(5)?;
-        assert!(game.make_move(5).is_err());
-        Ok(())
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), Some(X)],
+            vec![Some(O), Some(O), Some(O)],
+            vec![None, None, None],
+        ];
+        let err = game.validate().unwrap_err();
+        assert_eq!("Both players completed a line", err.to_string());
     }
 
     #[test]
-    fn is_full() {
+    fn move_after_win() {
         let mut game = Game::new();
-        game.arr_squares = [
-            [Some(X), Some(O), Some(X)],
-            [Some(O), Some(O), Some(X)],
-            [Some(O), Some(X), Some(X)],
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), Some(X)],
+            vec![Some(O), Some(O), None],
+            vec![Some(O), None, None],
         ];
-        assert!(game.is_full());
+        assert!(game.validate().is_err());
     }
 
     #[test]
-    fn is_not_full() {
+    fn row_win_reason() {
         let mut game = Game::new();
-        game.arr_squares = [
-            [Some(X), Some(O), Some(X)],
-            [Some(O), None, Some(X)],
-            [Some(O), Some(X), Some(X)],
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), Some(X)],
+            vec![Some(O), Some(O), None],
+            vec![None, None, None],
         ];
-        assert!(!game.is_full());
+        assert_eq!(
+            Some("X completed the top row".to_owned()),
+            game.end_reason()
+        );
+
+        let mut game = Game::with_symbols('#', '@');
+        game.arr_squares[0] = vec![Some(X), Some(X), Some(X)];
+        assert_eq!(
+            Some("# completed the top row".to_owned()),
+            game.end_reason()
+        );
+        let mut game = Game::with_symbols('#', '@');
+        game.resign(O);
+        assert_eq!(Some("@ resigned".to_owned()), game.end_reason());
     }
 
     #[test]
-    fn draw_checking() {
+    fn draw_reason() {
         let mut game = Game::new();
-        game.arr_squares = [
-            [Some(X), Some(O), Some(O)],
-            [Some(O), Some(X), Some(X)],
-            [Some(O), Some(X), Some(O)],
+        assert_eq!(None, game.end_reason());
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), Some(O)],
+            vec![Some(O), Some(X), Some(X)],
+            vec![Some(O), Some(X), Some(O)],
         ];
+        assert_eq!(Some("board full — no winner".to_owned()), game.end_reason());
+    }
+
+    #[test]
+    fn larger_board_win_length() -> Result<()> {
+        let mut game = Game::with_size(4, 4);
+        for i in [1, 5, 2, 6, 3, 7] {
+            game.make_move(i)?;
+        }
         assert_eq!(None, game.get_winner());
+        game.make_move(4)?;
+        assert_eq!(Some(X), game.get_winner());
+        assert_eq!(
+            Some("X completed row 1 from square 1".to_owned()),
+            game.end_reason()
+        );
+        assert!(game.make_move(17).is_err());
+        Ok(())
     }
 
     #[test]
-    fn loser_checking() {
+    fn short_run_reasons() -> Result<()> {
+        let mut game = Game::with_size(3, 2);
+        for i in [1, 4, 2] {
+            game.make_move(i)?;
+        }
+        assert_eq!(
+            Some("X completed row 1 from square 1".to_owned()),
+            game.end_reason()
+        );
+
+        let mut game = Game::with_size(4, 3);
+        for i in [2, 1, 7, 3, 12] {
+            game.make_move(i)?;
+        }
+        assert_eq!(
+            Some("X completed the diagonal down to the right from square 2".to_owned()),
+            game.end_reason()
+        );
+        let mut game = Game::with_size(4, 3);
+        for i in [4, 1, 7, 2, 10] {
+            game.make_move(i)?;
+        }
+        assert_eq!(
+            Some("X completed the diagonal down to the left from square 4".to_owned()),
+            game.end_reason()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn larger_board_diagonals() -> Result<()> {
+        let mut game = Game::with_size(5, 3);
+        for i in [3, 1, 9, 2, 15] {
+            game.make_move(i)?;
+        }
+        assert_eq!(Some(X), game.get_winner());
+        Ok(())
+    }
+
+    #[test]
+    fn opposite_corners() -> Result<()> {
+        for (corner, opposite) in [(1, 9), (3, 7), (7, 3), (9, 1)] {
+            assert_eq!(opposite, opposite_corner(corner)?);
+        }
+        for i in [0, 2, 4, 5, 6, 8, 10] {
+            assert!(opposite_corner(i).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn algebraic_coords() -> Result<()> {
+        let tests = [(1, "a1"), (3, "c1"), (5, "b2"), (7, "a3"), (9, "c3")];
+        for (i, coord) in tests {
+            assert_eq!(i, parse_algebraic(coord, 3)?);
+            assert_eq!(coord, to_algebraic(i, 3)?);
+        }
+        for coord in ["", "a", "d1", "a4", "a0", "1a", "b22"] {
+            assert!(parse_algebraic(coord, 3).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn best_move_wins() {
         let mut game = Game::new();
-        game.arr_squares = [
-            [Some(X), Some(X), Some(O)],
-            [None, Some(O), Some(X)],
-            [Some(O), Some(O), Some(X)],
+        game.arr_squares = vec![
+            vec![Some(X), None, Some(O)],
+            vec![Some(X), Some(O), None],
+            vec![None, None, None],
         ];
-        assert_eq!(Some(O), game.get_winner());
+        assert_eq!(Some(7), game.best_move());
     }
 
     #[test]
-    fn winner_checking() {
+    fn hint_leaves_game_unchanged() -> Result<()> {
+        let game = Game::from_moves(&[1, 4, 2, 5])?;
+        let before = game.clone();
+        assert_eq!(Some(3), game.hint());
+        assert_eq!(before, game);
+        assert_eq!(X, game.get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn best_move_blocks() {
         let mut game = Game::new();
-        game.arr_squares = [
-            [Some(X), Some(O), Some(X)],
-            [Some(O), Some(O), Some(X)],
-            [Some(O), Some(X), Some(X)],
+        game.arr_squares = vec![
+            vec![Some(X), Some(X), None],
+            vec![None, Some(O), None],
+            vec![None, None, None],
         ];
-        assert_eq!(Some(X), game.get_winner());
+        game.next_player = O;
+        assert_eq!(Some(3), game.best_move());
+    }
+
+    #[test]
+    fn no_best_move_when_over() {
+        let mut game = Game::new();
+        game.arr_squares = vec![
+            vec![Some(X), Some(O), Some(X)],
+            vec![Some(O), Some(O), Some(X)],
+            vec![Some(O), Some(X), Some(X)],
+        ];
+        assert_eq!(None, game.best_move());
     }
 
     #[test]
@@ -314,8 +2115,19 @@ mod tests {
             (9, 2, 2),
         ];
         for t in tests {
-            assert_eq!((t.1, t.2), get_coords(t.0)?);
+            assert_eq!((t.1, t.2), get_coords(t.0, 3)?);
         }
+        assert!(get_coords(0, 3).is_err());
+        assert!(get_coords(10, 3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn wider_coords() -> Result<()> {
+        assert_eq!((0, 3), get_coords(4, 4)?);
+        assert_eq!((1, 0), get_coords(5, 4)?);
+        assert_eq!((3, 3), get_coords(16, 4)?);
+        assert!(get_coords(17, 4).is_err());
         Ok(())
     }
 }
@@ -1,9 +1,13 @@
 use std::fmt::Display;
 
 use colored::{ColoredString, Colorize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-/// Represents the player, but also any square they have played
-#[derive(Debug, PartialEq, Clone, Copy)]
+/// Represents the player, but also any square they have played. [`Player::X`] orders before
+/// [`Player::O`].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Player {
     X,
     O,
@@ -17,10 +21,32 @@ impl Player {
         }
     }
 
+    pub fn to_char(&self) -> char {
+        match self {
+            Self::X => 'X',
+            Self::O => 'O',
+        }
+    }
+
+    /// Parses `X` or `O`, in either case
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'X' => Some(Self::X),
+            'O' => Some(Self::O),
+            _ => None,
+        }
+    }
+
     pub fn colored(&self) -> ColoredString {
+        self.color_symbol(self.to_char())
+    }
+
+    /// Colors any symbol the way this player's pieces are colored
+    pub fn color_symbol(&self, symbol: char) -> ColoredString {
+        let symbol = symbol.to_string();
         match self {
-            Self::X => "X".blue(),
-            Self::O => "O".red(),
+            Self::X => symbol.blue(),
+            Self::O => symbol.red(),
         }
     }
 
@@ -37,7 +63,7 @@ impl Display for Player {
 
 #[cfg(test)]
 mod tests {
-    use crate::Player::*;
+    use crate::Player::{self, *};
     use colored::Colorize;
 
     #[test]
@@ -48,12 +74,34 @@ mod tests {
         assert_eq!(X, o.next());
     }
 
+    #[test]
+    fn player_as_char() {
+        assert_eq!('X', X.to_char());
+        assert_eq!('O', O.to_char());
+    }
+
+    #[test]
+    fn player_from_char() {
+        assert_eq!(Some(X), Player::from_char('X'));
+        assert_eq!(Some(X), Player::from_char('x'));
+        assert_eq!(Some(O), Player::from_char('O'));
+        assert_eq!(Some(O), Player::from_char('o'));
+        assert_eq!(None, Player::from_char('0'));
+        assert_eq!(None, Player::from_char('.'));
+    }
+
     #[test]
     fn player_is_colored() {
         assert_eq!(X.colored(), "X".blue());
         assert_eq!(O.colored(), "O".red());
     }
 
+    #[test]
+    fn symbol_is_colored() {
+        assert_eq!(X.color_symbol('#'), "#".blue());
+        assert_eq!(O.color_symbol('@'), "@".red());
+    }
+
     #[test]
     fn player_is_colored_bold_underlined() {
         assert_eq!(X.colored_highlighted(), "X".blue().bold().underline());
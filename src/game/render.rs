@@ -0,0 +1,568 @@
+use std::{
+    fmt::{self, Display, Write},
+    io,
+};
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+
+use super::{get_coords, to_algebraic, Game, GameStatus};
+
+/// Options controlling how a board is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    /// Number of spaces on each side of the symbol inside a cell
+    pub cell_padding: usize,
+    /// Whether the symbols are colored
+    pub mode: RenderMode,
+    /// How empty squares are labelled
+    pub labels: Labels,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            cell_padding: 1,
+            mode: RenderMode::Colored,
+            labels: Labels::Numeric,
+        }
+    }
+}
+
+/// The labels shown on empty squares, matching the way moves are entered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Labels {
+    /// The square number, 1 to 9
+    Numeric,
+    /// The algebraic coordinate, a1 to c3, see [`parse_algebraic`](super::parse_algebraic)
+    Algebraic,
+}
+
+/// Whether symbols are drawn with terminal colors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderMode {
+    Colored,
+    Plain,
+}
+
+impl RenderMode {
+    /// Picks colors for interactive terminals and plain text otherwise, e.g. when piped
+    pub fn detect(is_terminal: bool) -> Self {
+        if is_terminal {
+            Self::Colored
+        } else {
+            Self::Plain
+        }
+    }
+
+    /// Makes every subsequent rendering of the process use this mode
+    pub fn apply(self) {
+        match self {
+            Self::Colored => colored::control::unset_override(),
+            Self::Plain => colored::control::set_override(false),
+        }
+    }
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_with(f, &RenderOptions::default())
+    }
+}
+
+impl Game {
+    /// Renders the board like its [`Display`] implementation, but with the given options
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let mut out = String::new();
+        // Writing into a String cannot fail
+        let _ = self.write_with(&mut out, options);
+        out
+    }
+
+    /// The lines of the board as drawn by its [`Display`] implementation, without line breaks, e.g.
+    /// to lay out several boards next to each other like [`render_side_by_side`]
+    pub fn render_lines(&self) -> Vec<String> {
+        self.to_string().lines().map(str::to_owned).collect()
+    }
+
+    /// Describes the board in words, row by row, followed by whose turn it is or the result, e.g.
+    /// for screen readers. The description never contains terminal colors.
+    pub fn describe(&self) -> String {
+        let mut out: Vec<String> = self
+            .arr_squares
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                let squares: Vec<String> = row
+                    .iter()
+                    .map(|square| match square {
+                        Some(player) => self.symbol(*player).to_string(),
+                        None => "empty".to_owned(),
+                    })
+                    .collect();
+                format!("Row {}: {}.", y + 1, squares.join(", "))
+            })
+            .collect();
+        out.push(match self.status() {
+            GameStatus::InProgress => format!("It's {}'s turn.", self.symbol(self.next_player)),
+            GameStatus::Won(player) => format!("{} won.", self.symbol(player)),
+            GameStatus::Drawn => "It's a draw.".to_owned(),
+            GameStatus::Resigned { winner } => format!(
+                "{} resigned, {} won.",
+                self.symbol(winner.next()),
+                self.symbol(winner)
+            ),
+            GameStatus::TimedOut { winner } => format!(
+                "{} ran out of time, {} won.",
+                self.symbol(winner.next()),
+                self.symbol(winner)
+            ),
+        });
+        out.join(" ")
+    }
+
+    /// Writes the board like its [`Display`] implementation straight into `w`, e.g. a file or a
+    /// socket, without building a string first.
+    ///
+    /// # Errors
+    ///
+    /// If writing to `w` fails.
+    pub fn write_board<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut writer = IoWriter {
+            inner: w,
+            error: None,
+        };
+        self.write_with(&mut writer, &RenderOptions::default())
+            .map_err(|_| {
+                writer
+                    .error
+                    .unwrap_or_else(|| io::Error::other("Could not draw the board"))
+            })
+    }
+
+    /// Renders the board with plain symbols and no terminal colors, whether or not colors are
+    /// enabled, e.g. for logs
+    pub fn render_plain(&self) -> String {
+        self.render(&RenderOptions {
+            mode: RenderMode::Plain,
+            ..Default::default()
+        })
+    }
+
+    fn write_with(&self, f: &mut impl Write, options: &RenderOptions) -> fmt::Result {
+        let n = self.size;
+        // Wide enough for the label of the last square
+        let content_width = match options.labels {
+            Labels::Numeric => (n * n).to_string().len(),
+            Labels::Algebraic => 1 + n.to_string().len(),
+        };
+        let width = 2 * options.cell_padding + content_width;
+        let padding = " ".repeat(options.cell_padding);
+        let blank = format!("|{}", format!("{}|", " ".repeat(width)).repeat(n));
+        let bottom = format!("|{}", format!("{}|", "_".repeat(width)).repeat(n));
+
+        let winning_line = self
+            .winning_line()
+            .map(|(_, line)| line)
+            .unwrap_or_default();
+
+        writeln!(f, " {}", "_".repeat(n * width + n - 1))?;
+        writeln!(f, "{blank}")?;
+        for i in 1..=n * n {
+            let (y, x) = get_coords(i, n).map_err(|_| fmt::Error)?;
+            if y != 0 && x == 0 {
+                writeln!(f, "{bottom}")?;
+                writeln!(f, "{blank}")?;
+            }
+            let str = match self.arr_squares[y][x] {
+                Some(player) if options.mode == RenderMode::Plain => {
+                    self.symbol(player).to_string()
+                }
+                Some(player)
+                    if self.last_move() == Some((i, player)) || winning_line.contains(&(y, x)) =>
+                {
+                    let symbol = player.color_symbol(self.symbol(player));
+                    symbol.bold().underline().to_string()
+                }
+                Some(player) => player.color_symbol(self.symbol(player)).to_string(),
+                None if options.labels == Labels::Algebraic => {
+                    to_algebraic(i, n).map_err(|_| fmt::Error)?
+                }
+                None => i.to_string(),
+            };
+            let fill = " ".repeat(content_width - visible_width(&str));
+            write!(f, "|{padding}{}{fill}{padding}", str)?;
+            if x == n - 1 {
+                writeln!(f, "|")?;
+            }
+        }
+        write!(f, "{bottom}")
+    }
+
+    /// Plays `history` from the start and renders every board along the way without colors,
+    /// from the empty board to the final position, e.g. as frames of an animation.
+    ///
+    /// # Errors
+    ///
+    /// If any of the moves is illegal.
+    pub fn frames(history: &[usize]) -> Result<Vec<String>> {
+        let mut game = Game::new();
+        let mut frames = vec![game.render_plain()];
+        for (n, &i) in history.iter().enumerate() {
+            game.make_move(i)
+                .map_err(|err| anyhow!("Move {} is illegal: {err}", n + 1))?;
+            frames.push(game.render_plain());
+        }
+        Ok(frames)
+    }
+}
+
+/// Renders two boards next to each other, labelled "Before" and "After", to visualize the effect
+/// of a move or a variation.
+///
+/// Both boards are expected to have the same size, as their rows are paired up line by line.
+pub fn render_side_by_side(a: &Game, b: &Game) -> String {
+    const GAP: &str = "    ";
+
+    let left = a.render_lines();
+    let right = b.render_lines();
+    let width = left.iter().map(|l| visible_width(l)).max().unwrap_or(0);
+
+    let mut out = format!("{:<width$}{GAP}{}", "Before", "After");
+    for (l, r) in left.iter().zip(&right) {
+        let padding = " ".repeat(width - visible_width(l));
+        out.push_str(&format!("\n{l}{padding}{GAP}{r}"));
+    }
+    out
+}
+
+/// Forwards formatted text to an [`io::Write`], keeping the error that interrupted it
+struct IoWriter<'a, W> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+/// The number of characters a line takes up in the terminal, ignoring ANSI color sequences
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player::*;
+
+    #[test]
+    fn display_game() -> Result<()> {
+        let mut game = Game::new();
+        assert_eq!(
+            " ___________
+|   |   |   |
+| 1 | 2 | 3 |
+|___|___|___|
+|   |   |   |
+| 4 | 5 | 6 |
+|___|___|___|
+|   |   |   |
+| 7 | 8 | 9 |
+|___|___|___|"
+                .to_owned(),
+            game.to_string()
+        );
+        game.make_move(1)?;
+        assert_eq!(
+            format!(
+                " ___________
+|   |   |   |
+| {} | 2 | 3 |
+|___|___|___|
+|   |   |   |
+| 4 | 5 | 6 |
+|___|___|___|
+|   |   |   |
+| 7 | 8 | 9 |
+|___|___|___|",
+                X.colored_highlighted()
+            ),
+            game.to_string()
+        );
+        game.make_move(2)?;
+        assert_eq!(
+            format!(
+                " ___________
+|   |   |   |
+| {} | {} | 3 |
+|___|___|___|
+|   |   |   |
+| 4 | 5 | 6 |
+|___|___|___|
+|   |   |   |
+| 7 | 8 | 9 |
+|___|___|___|",
+                X.colored(),
+                O.colored_highlighted()
+            ),
+            game.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn display_winning_line() -> Result<()> {
+        let mut game = Game::new();
+        for i in [1, 2, 5, 3, 9] {
+            game.make_move(i)?;
+        }
+        assert_eq!(
+            format!(
+                " ___________
+|   |   |   |
+| {} | {} | {} |
+|___|___|___|
+|   |   |   |
+| 4 | {} | 6 |
+|___|___|___|
+|   |   |   |
+| 7 | 8 | {} |
+|___|___|___|",
+                X.colored_highlighted(),
+                O.colored(),
+                O.colored(),
+                X.colored_highlighted(),
+                X.colored_highlighted()
+            ),
+            game.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn write_board_to_bytes() -> Result<()> {
+        let mut bytes = Vec::new();
+        Game::new().write_board(&mut bytes)?;
+        assert_eq!(
+            b" ___________
+|   |   |   |
+| 1 | 2 | 3 |
+|___|___|___|
+|   |   |   |
+| 4 | 5 | 6 |
+|___|___|___|
+|   |   |   |
+| 7 | 8 | 9 |
+|___|___|___|",
+            bytes.as_slice()
+        );
+
+        let game = Game::from_moves(&[5, 1])?;
+        let mut bytes = Vec::new();
+        game.write_board(&mut bytes)?;
+        assert_eq!(game.to_string().into_bytes(), bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn write_board_error() {
+        let mut full = [0u8; 10];
+        let err = Game::new()
+            .write_board(&mut full.as_mut_slice())
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::WriteZero, err.kind());
+    }
+
+    #[test]
+    fn plain_rendering() -> Result<()> {
+        let plain = Game::from_moves(&[1, 2, 5, 3, 9])?.render_plain();
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(
+            " ___________
+|   |   |   |
+| X | O | O |
+|___|___|___|
+|   |   |   |
+| 4 | X | 6 |
+|___|___|___|
+|   |   |   |
+| 7 | 8 | X |
+|___|___|___|",
+            plain
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn description() -> Result<()> {
+        let mut game = Game::from_moves(&[1, 5, 3])?;
+        assert_eq!(
+            "Row 1: X, empty, X. Row 2: empty, O, empty. Row 3: empty, empty, empty. \
+             It's O's turn.",
+            game.describe()
+        );
+        game.make_move(2)?;
+        assert_eq!(
+            "Row 1: X, O, X. Row 2: empty, O, empty. Row 3: empty, empty, empty. It's X's turn.",
+            game.describe()
+        );
+        game.make_move(4)?;
+        game.make_move(8)?;
+        assert!(game.describe().ends_with("Row 3: empty, O, empty. O won."));
+        assert!(!game.describe().contains('\x1b'));
+        Ok(())
+    }
+
+    #[test]
+    fn board_lines() -> Result<()> {
+        let game = Game::from_moves(&[5, 1])?;
+        let lines = game.render_lines();
+        assert_eq!(10, lines.len());
+        assert_eq!(" ___________", lines[0]);
+        assert_eq!(game.to_string(), lines.join("\n"));
+
+        assert_eq!(13, Game::with_size(4, 3).render_lines().len());
+        Ok(())
+    }
+
+    #[test]
+    fn custom_symbols() -> Result<()> {
+        let mut game = Game::with_symbols('#', '@');
+        game.make_move(1)?;
+        game.make_move(5)?;
+        let plain = game.render(&RenderOptions {
+            mode: RenderMode::Plain,
+            ..Default::default()
+        });
+        assert!(plain.contains("| # | 2 | 3 |"));
+        assert!(plain.contains("| 4 | @ | 6 |"));
+        assert!(game
+            .to_string()
+            .contains(&format!("| {} ", O.color_symbol('@').bold().underline())));
+        assert!(!game.to_string().contains('X'));
+
+        let mut game = Game::new();
+        game.make_move(1)?;
+        assert!(game.to_string().contains('X'));
+        Ok(())
+    }
+
+    #[test]
+    fn display_larger_game() -> Result<()> {
+        let mut game = Game::with_size(4, 3);
+        game.make_move(16)?;
+        assert_eq!(
+            format!(
+                " ___________________
+|    |    |    |    |
+| 1  | 2  | 3  | 4  |
+|____|____|____|____|
+|    |    |    |    |
+| 5  | 6  | 7  | 8  |
+|____|____|____|____|
+|    |    |    |    |
+| 9  | 10 | 11 | 12 |
+|____|____|____|____|
+|    |    |    |    |
+| 13 | 14 | 15 | {}  |
+|____|____|____|____|",
+                X.colored_highlighted()
+            ),
+            game.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cell_padding() {
+        let game = Game::new();
+        let default = game.render(&RenderOptions::default());
+        let roomy = game.render(&RenderOptions {
+            cell_padding: 2,
+            ..Default::default()
+        });
+        assert_eq!(game.to_string(), default);
+        assert!(default.lines().skip(1).all(|line| line.len() == 13));
+        assert!(roomy.lines().skip(1).all(|line| line.len() == 19));
+        assert_eq!(" _________________", roomy.lines().next().unwrap());
+        assert_eq!("|  1  |  2  |  3  |", roomy.lines().nth(2).unwrap());
+    }
+
+    #[test]
+    fn algebraic_labels() -> Result<()> {
+        let options = RenderOptions {
+            labels: Labels::Algebraic,
+            mode: RenderMode::Plain,
+            ..Default::default()
+        };
+        let mut game = Game::new();
+        let board = game.render(&options);
+        assert!(board.contains("|    |    |    |"));
+        assert!(board.contains("| a1 | b1 | c1 |"));
+        assert!(board.contains("| a3 | b3 | c3 |"));
+        assert!(!board.contains('5'));
+
+        game.make_move(5)?;
+        assert!(game.render(&options).contains("| a2 | X  | c2 |"));
+        Ok(())
+    }
+
+    #[test]
+    fn game_frames() -> Result<()> {
+        let history = [5, 1, 9];
+        let frames = Game::frames(&history)?;
+        assert_eq!(history.len() + 1, frames.len());
+        assert!(frames.iter().all(|frame| !frame.contains('\x1b')));
+        assert!(frames[0].contains("| 4 | 5 | 6 |"));
+        assert!(frames[1].contains("| 4 | X | 6 |"));
+        assert!(frames[3].contains("| 7 | 8 | X |"));
+        assert!(Game::frames(&[5, 5]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn render_mode_detection() {
+        assert_eq!(RenderMode::Colored, RenderMode::detect(true));
+        assert_eq!(RenderMode::Plain, RenderMode::detect(false));
+    }
+
+    #[test]
+    fn side_by_side() -> Result<()> {
+        let before = Game::new();
+        let mut after = Game::new();
+        after.make_move(5)?;
+        assert_eq!(
+            format!(
+                "Before           After
+ ___________      ___________
+|   |   |   |    |   |   |   |
+| 1 | 2 | 3 |    | 1 | 2 | 3 |
+|___|___|___|    |___|___|___|
+|   |   |   |    |   |   |   |
+| 4 | 5 | 6 |    | 4 | {} | 6 |
+|___|___|___|    |___|___|___|
+|   |   |   |    |   |   |   |
+| 7 | 8 | 9 |    | 7 | 8 | 9 |
+|___|___|___|    |___|___|___|",
+                X.colored_highlighted()
+            ),
+            render_side_by_side(&before, &after)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+
+use super::{get_coords, Game, Player};
+
+impl Game {
+    /// Serializes the whole game, including whose turn it is, into JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Restores a game serialized with [`Game::to_json`]. If the turns are timed, the clock of the
+    /// current turn starts over. Saves without `next_player` get it from the counts of pieces, see
+    /// [`Game::infer_next_player`].
+    ///
+    /// # Errors
+    ///
+    /// If the JSON is malformed or describes a game that cannot occur, see [`Game::validate`].
+    pub fn from_json(s: &str) -> Result<Game> {
+        let json: serde_json::Value = serde_json::from_str(s)?;
+        let inferred = json.get("next_player").is_none();
+        let mut game: Game = serde_json::from_value(json)?;
+        if inferred {
+            game.next_player = game.infer_next_player_from_counts()?;
+        }
+        game.check_consistency()?;
+        game.start_turn();
+        Ok(game)
+    }
+
+    /// Writes the game to `path` as JSON, replacing the file if it exists
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?)
+            .with_context(|| format!("Could not save the game to {}", path.display()))
+    }
+
+    /// Restores a game written with [`Game::save`]
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read, or its contents are rejected by [`Game::from_json`].
+    pub fn load(path: &Path) -> Result<Game> {
+        fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Game::from_json(&json))
+            .with_context(|| format!("Could not load a game from {}", path.display()))
+    }
+
+    /// [`Game::infer_next_player`] for games where [`Player::O`] may have moved first
+    fn infer_next_player_from_counts(&self) -> Result<Player> {
+        if self.first_player == Player::X {
+            return Game::infer_next_player(&self.arr_squares);
+        }
+        // Swaps the pieces so that the first player is X, then swaps the answer back
+        let swapped: Vec<Vec<_>> = self
+            .arr_squares
+            .iter()
+            .map(|row| row.iter().map(|square| square.map(|p| p.next())).collect())
+            .collect();
+        Ok(Game::infer_next_player(&swapped)?.next())
+    }
+
+    fn check_consistency(&self) -> Result<()> {
+        let n = self.size;
+        if self.arr_squares.len() != n || self.arr_squares.iter().any(|row| row.len() != n) {
+            return Err(anyhow!("The board must have {n} rows of {n} squares"));
+        }
+        if !(1..=n).contains(&self.win_len) {
+            return Err(anyhow!("The win length must be between 1 and {n}"));
+        }
+        let pieces = self.piece_count(Player::X) + self.piece_count(Player::O);
+        if self.history.len() > pieces {
+            return Err(anyhow!(
+                "{} moves were played but the board holds {pieces} pieces",
+                self.history.len()
+            ));
+        }
+        // The players took turns, the last move being the one before the current player's turn.
+        // With only played moves on the board, this starts with the first player.
+        let mut player = self.next_player;
+        for (k, &i) in self.history.iter().enumerate().rev() {
+            player = player.next();
+            if self.history[..k].contains(&i) {
+                return Err(anyhow!("Square {i} was played twice"));
+            }
+            let (y, x) = get_coords(i, n)?;
+            match self.arr_squares[y][x] {
+                None => return Err(anyhow!("Square {i} was played but is empty")),
+                Some(by) if by != player => {
+                    return Err(anyhow!(
+                        "Square {i} holds {} but was played by {}",
+                        by.to_char(),
+                        player.to_char()
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        if self.history.len() == pieces && pieces > 0 && player != self.first_player {
+            return Err(anyhow!(
+                "The first move was played by {} instead of {}",
+                player.to_char(),
+                self.first_player.to_char()
+            ));
+        }
+
+        self.validate()
+    }
+}
+
+/// The first player of games saved before it could be chosen
+pub(super) fn default_first_player() -> Player {
+    Player::X
+}
+
+/// The symbols of games saved before they could be customized
+pub(super) fn default_symbols() -> [char; 2] {
+    [Player::X.to_char(), Player::O.to_char()]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::*;
+    use crate::Player::*;
+
+    #[test]
+    fn json_round_trip() -> Result<()> {
+        let mut game = Game::new();
+        assert_eq!(game, Game::from_json(&game.to_json()?)?);
+        for i in [5, 1, 9] {
+            game.make_move(i)?;
+        }
+        let restored = Game::from_json(&game.to_json()?)?;
+        assert_eq!(game, restored);
+        assert_eq!(O, restored.get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn clock_restarts_on_load() -> Result<()> {
+        let limit = Duration::from_millis(20);
+        let game = Game::new().with_turn_limit(limit);
+        let restored = Game::from_json(&game.to_json()?)?;
+        assert_eq!(Some(limit), restored.turn_limit());
+        thread::sleep(limit * 2);
+        assert_eq!(Some(Duration::ZERO), restored.time_remaining());
+        Ok(())
+    }
+
+    #[test]
+    fn older_saves_load() -> Result<()> {
+        let game = Game::from_moves(&[5, 1])?;
+        let mut json: serde_json::Value = serde_json::from_str(&game.to_json()?)?;
+        let fields = json.as_object_mut().expect("a game is saved as an object");
+        fields.remove("first_player");
+        fields.remove("symbols");
+        let restored = Game::from_json(&json.to_string())?;
+        assert_eq!(game, restored);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_next_player_is_inferred() -> Result<()> {
+        for game in [
+            Game::new(),
+            Game::from_moves(&[5])?,
+            Game::from_moves(&[5, 1])?,
+            Game::builder().first_player(O).build()?,
+        ] {
+            let mut json: serde_json::Value = serde_json::from_str(&game.to_json()?)?;
+            let fields = json.as_object_mut().expect("a game is saved as an object");
+            fields.remove("next_player");
+            assert_eq!(game, Game::from_json(&json.to_string())?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn larger_board_round_trip() -> Result<()> {
+        let mut game = Game::with_size(4, 3);
+        game.make_move(16)?;
+        assert_eq!(game, Game::from_json(&game.to_json()?)?);
+        Ok(())
+    }
+
+    #[test]
+    fn file_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("game.json");
+        let game = Game::from_moves(&[5, 1, 9])?;
+        game.save(&path)?;
+        assert_eq!(game, Game::load(&path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn load_errors_name_the_path() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let missing = dir.path().join("missing.json");
+        let err = Game::load(&missing).unwrap_err();
+        assert!(err.to_string().contains("missing.json"));
+
+        let garbage = dir.path().join("garbage.json");
+        fs::write(&garbage, "not json")?;
+        let err = Game::load(&garbage).unwrap_err();
+        assert!(err.to_string().contains("garbage.json"));
+        Ok(())
+    }
+
+    #[test]
+    fn inconsistent_history() -> Result<()> {
+        let game = Game::from_moves(&[5, 1])?;
+        let json = game.to_json()?;
+        assert!(json.contains(r#""history":[5,1]"#));
+
+        let duplicate = json.replace(r#""history":[5,1]"#, r#""history":[5,5]"#);
+        let err = Game::from_json(&duplicate).unwrap_err();
+        assert_eq!("Square 5 was played twice", err.to_string());
+
+        let swapped = json.replace(r#""history":[5,1]"#, r#""history":[1,5]"#);
+        assert!(Game::from_json(&swapped).is_err());
+
+        let longer = json.replace(r#""history":[5,1]"#, r#""history":[5,1,9]"#);
+        assert!(Game::from_json(&longer).is_err());
+
+        // Moves played on a position that was set up
+        let mut game = Game::decode("X..|...|...")?;
+        game.make_move(5)?;
+        assert_eq!(game, Game::from_json(&game.to_json()?)?);
+        Ok(())
+    }
+
+    #[test]
+    fn inconsistent_counts() -> Result<()> {
+        let mut game = Game::new();
+        game.arr_squares[0][0] = Some(X);
+        game.arr_squares[0][1] = Some(X);
+        game.next_player = O;
+        assert!(Game::from_json(&game.to_json()?).is_err());
+
+        let mut game = Game::new();
+        game.make_move(5)?;
+        game.next_player = X;
+        assert!(Game::from_json(&game.to_json()?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_json() {
+        assert!(Game::from_json("{}").is_err());
+        assert!(Game::from_json(
+            r#"{"next_player":"X","board":[[null]],"history":[],"size":3,"win_len":3}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Utilities for working with games recorded as lists of square numbers
+
+use anyhow::{anyhow, Result};
+
+use super::{Game, GameStatus};
+
+impl Game {
+    /// Replays `moves` from the empty board like [`Game::from_moves`], returning a snapshot of
+    /// the game after every move.
+    ///
+    /// # Errors
+    ///
+    /// If a move is illegal, naming its step, counted from 1.
+    pub fn replay_steps(moves: &[usize]) -> Result<Vec<Game>> {
+        let mut game = Game::new();
+        let mut steps = Vec::with_capacity(moves.len());
+        for (n, &i) in moves.iter().enumerate() {
+            game.make_move(i)
+                .map_err(|err| anyhow!("Step {} ({i}) is illegal: {err}", n + 1))?;
+            steps.push(game.clone());
+        }
+        Ok(steps)
+    }
+
+    /// Writes the moves played so far as numbered pairs with the player and the square, like
+    /// `1. X5 O1 2. X9`. A winning move is marked with `#`, and a finished game ends with its
+    /// result, e.g. `X wins`, `O resigns`, `X loses on time` or `Draw`.
+    pub fn transcript(&self) -> String {
+        let mut player = self.first_player;
+        let mut parts = Vec::new();
+        for (n, &i) in self.history.iter().enumerate() {
+            if n % 2 == 0 {
+                parts.push(format!("{}.", n / 2 + 1));
+            }
+            parts.push(format!("{}{i}", player.to_char()));
+            player = player.next();
+        }
+        match self.status() {
+            GameStatus::InProgress => {}
+            GameStatus::Won(winner) => {
+                // Under the misère rule the last move completed a line and lost instead
+                if winner == player.next() {
+                    if let Some(last) = parts.last_mut() {
+                        last.push('#');
+                    }
+                }
+                parts.push(format!("{} wins", winner.to_char()));
+            }
+            GameStatus::Drawn => parts.push("Draw".to_owned()),
+            GameStatus::Resigned { winner } => {
+                parts.push(format!("{} resigns", winner.next().to_char()))
+            }
+            GameStatus::TimedOut { winner } => {
+                parts.push(format!("{} loses on time", winner.next().to_char()))
+            }
+        }
+        parts.join(" ")
+    }
+}
+
+/// The index of the first move where the two transcripts differ, or [`None`] if they are
+/// identical or one continues the other.
+pub fn divergence_point(a: &[usize], b: &[usize]) -> Option<usize> {
+    a.iter().zip(b).position(|(a, b)| a != b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Player::*, WinRule};
+
+    #[test]
+    fn identical_transcripts() {
+        assert_eq!(None, divergence_point(&[5, 1, 9], &[5, 1, 9]));
+        assert_eq!(None, divergence_point(&[], &[]));
+    }
+
+    #[test]
+    fn prefix_transcripts() {
+        assert_eq!(None, divergence_point(&[5, 1], &[5, 1, 9]));
+        assert_eq!(None, divergence_point(&[5, 1, 9], &[]));
+    }
+
+    #[test]
+    fn diverging_transcripts() {
+        assert_eq!(Some(0), divergence_point(&[5, 1], &[1, 5]));
+        assert_eq!(Some(2), divergence_point(&[5, 1, 9, 3], &[5, 1, 3]));
+    }
+
+    #[test]
+    fn won_game_transcript() -> Result<()> {
+        let game = Game::from_moves(&[5, 1, 9])?;
+        assert_eq!("1. X5 O1 2. X9", game.transcript());
+        let game = Game::from_moves(&[1, 4, 2, 5, 3])?;
+        assert_eq!("1. X1 O4 2. X2 O5 3. X3# X wins", game.transcript());
+        let game = Game::from_moves(&[5, 1, 3, 7, 9, 4])?;
+        assert_eq!("1. X5 O1 2. X3 O7 3. X9 O4# O wins", game.transcript());
+
+        let mut game = Game::builder().win_rule(WinRule::Misere).build()?;
+        for i in [1, 4, 2, 5, 3] {
+            game.make_move(i)?;
+        }
+        assert_eq!("1. X1 O4 2. X2 O5 3. X3 O wins", game.transcript());
+        Ok(())
+    }
+
+    #[test]
+    fn drawn_game_transcript() -> Result<()> {
+        assert_eq!("", Game::new().transcript());
+        let game = Game::from_moves(&[1, 2, 3, 5, 8, 4, 6, 9, 7])?;
+        assert_eq!(
+            "1. X1 O2 2. X3 O5 3. X8 O4 4. X6 O9 5. X7 Draw",
+            game.transcript()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn step_replay() -> Result<()> {
+        let steps = Game::replay_steps(&[5, 1, 9, 3, 2])?;
+        assert_eq!(5, steps.len());
+        assert_eq!(Some(X), steps[0].square(5)?);
+        assert_eq!(8, steps[0].empty_count());
+        assert_eq!(Some(O), steps[1].square(1)?);
+        assert_eq!(None, steps[1].square(9)?);
+        assert_eq!(Some(X), steps[2].square(9)?);
+        assert_eq!(Some(O), steps[3].square(3)?);
+        assert_eq!(Some(X), steps[4].square(2)?);
+        assert_eq!(O, steps[4].get_player());
+        Ok(())
+    }
+
+    #[test]
+    fn illegal_step() {
+        let err = Game::replay_steps(&[5, 1, 5]).unwrap_err();
+        assert!(err.to_string().starts_with("Step 3 (5) is illegal"));
+        assert!(Game::replay_steps(&[10]).is_err());
+    }
+}
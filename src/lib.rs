@@ -0,0 +1,12 @@
+//! A 2-player tic-tac-toe game, usable both from the terminal binary and as a library
+
+pub mod cli;
+pub mod game;
+pub mod net;
+pub mod score;
+
+pub use cli::run_game;
+pub use game::{
+    Difficulty, Eval, Game, GameStatus, MoveError, MoveOutcome, Player, RenderMode, SquareType,
+    WinRule,
+};
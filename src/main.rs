@@ -1,50 +1,83 @@
-mod game;
-
 use std::{
-    io::{stdin, stdout, Write},
-    num::ParseIntError,
+    env,
+    io::{stdin, stdout, IsTerminal},
+    time::Duration,
 };
 
 use anyhow::{anyhow, Result};
 
-use game::{Game, Player};
+use tictactoe::{
+    cli::{replay, run_match, CliOptions},
+    net::{host_game, join_game},
+    Difficulty, Game, Player, RenderMode, WinRule,
+};
 
 fn main() -> Result<()> {
-    let mut game = Game::new();
-    let winner: Option<Player> = loop {
-        println!("{}", game);
-        loop {
-            print!("It's {}'s turn: ", game.get_player());
-            stdout().flush()?;
-            let mut str = String::new();
-            if stdin().read_line(&mut str).is_err() {
-                println!("Could not read the line");
-                continue;
-            }
-            let str = str.trim();
-            match str {
-                "q" | "quit" | "exit" => return Ok(()),
-                _ => {}
-            }
-            match str
-                .parse::<usize>()
-                .map_err(|err: ParseIntError| anyhow!(err))
-                .and_then(|i| game.make_move(i))
-            {
-                Ok(_) => break,
-                Err(err) => println!("{err}"),
-            };
-        }
-        if let Some(player) = game.get_winner() {
-            break Some(player);
-        } else if game.is_full() {
-            break None;
-        }
+    RenderMode::detect(stdin().is_terminal() && stdout().is_terminal()).apply();
+
+    let args: Vec<String> = env::args().collect();
+    let value_of = |flag: &str| {
+        let position = args.iter().position(|arg| arg == flag)?;
+        args.get(position + 1)
+    };
+    if let Some(addr) = value_of("--host") {
+        return host_game(addr);
+    }
+    if let Some(addr) = value_of("--join") {
+        return join_game(addr);
+    }
+    if let Some(moves) = value_of("--replay") {
+        let moves = moves
+            .split(',')
+            .map(|i| i.trim().parse())
+            .collect::<Result<Vec<usize>, _>>()?;
+        return replay(&moves, stdout(), Duration::from_secs(1));
+    }
+
+    let difficulty = match value_of("--difficulty") {
+        Some(level) => level.parse()?,
+        None => Difficulty::default(),
+    };
+
+    let win_rule = if args.iter().any(|arg| arg == "--misere") {
+        WinRule::Misere
+    } else {
+        WinRule::Normal
+    };
+    let new_game = Game::builder().win_rule(win_rule).build()?;
+
+    #[cfg(feature = "serde")]
+    let game = match value_of("--load") {
+        Some(path) => Game::load(path.as_ref())?,
+        None => new_game,
     };
-    println!("{}", game);
-    match winner {
-        Some(winner) => println!("The winner is {}", winner),
-        None => println!("It's a draw"),
+    #[cfg(not(feature = "serde"))]
+    let game = new_game;
+    let mut game = match value_of("--turn-limit") {
+        Some(seconds) => game.with_turn_limit(Duration::from_secs(seconds.parse()?)),
+        None => game,
+    };
+    if let Some(first) = value_of("--first") {
+        let mut chars = first.chars();
+        let player = match (chars.next(), chars.next()) {
+            (Some(c), None) => Player::from_char(c),
+            _ => None,
+        }
+        .ok_or_else(|| anyhow!("Unknown player {first}, expected X or O"))?;
+        game.set_first_player(player)?;
     }
+
+    let options = CliOptions {
+        confirm_moves: args.iter().any(|arg| arg == "--confirm"),
+        algebraic: args.iter().any(|arg| arg == "--algebraic"),
+        computer: args
+            .iter()
+            .any(|arg| arg == "--computer")
+            .then_some(Player::O),
+        difficulty,
+        #[cfg(feature = "serde")]
+        save_path: value_of("--load").map_or_else(|| "tictactoe.json".into(), Into::into),
+    };
+    run_match(game, stdin().lock(), stdout(), &options)?;
     Ok(())
 }
@@ -0,0 +1,110 @@
+//! Playing against someone on another machine over TCP
+//!
+//! The two sides exchange their moves as square numbers, one per line. Each side keeps its own
+//! [`Game`] and checks the moves it receives against it.
+
+use std::{
+    io::{stdin, stdout, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::{Game, Player};
+
+/// Waits for an opponent to connect on `addr`, then plays as X with the terminal
+pub fn host_game(addr: &str) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!("Waiting for an opponent on {}", listener.local_addr()?);
+    let (stream, peer) = listener.accept()?;
+    println!("{peer} joined the game");
+    play_session(stream, Player::X, stdin().lock(), stdout())?;
+    Ok(())
+}
+
+/// Connects to a game hosted on `addr`, then plays as O with the terminal
+pub fn join_game(addr: &str) -> Result<()> {
+    let stream = TcpStream::connect(addr)?;
+    play_session(stream, Player::O, stdin().lock(), stdout())?;
+    Ok(())
+}
+
+/// Plays a game over `stream` as `me`, reading the local player's moves from `input` and writing
+/// the board and prompts to `output`. Returns the winner, or [`None`] for a draw or when either
+/// side leaves the game early.
+///
+/// # Errors
+///
+/// If the opponent sends something that isn't a legal move, or writing to `output` fails.
+pub fn play_session<R: BufRead, W: Write>(
+    stream: TcpStream,
+    me: Player,
+    mut input: R,
+    mut output: W,
+) -> Result<Option<Player>> {
+    let mut peer = BufReader::new(stream.try_clone()?);
+    let mut stream = stream;
+    let mut game = Game::new();
+
+    loop {
+        writeln!(output, "{game}")?;
+        if let Some(winner) = game.get_winner() {
+            writeln!(output, "The winner is {winner}")?;
+            return Ok(Some(winner));
+        } else if game.is_full() {
+            writeln!(output, "It's a draw")?;
+            return Ok(None);
+        }
+
+        if game.get_player() == me {
+            let Some(i) = read_local_move(&mut game, &mut input, &mut output)? else {
+                writeln!(output, "You left the game")?;
+                return Ok(None);
+            };
+            if writeln!(stream, "{i}").is_err() {
+                writeln!(output, "Your opponent disconnected")?;
+                return Ok(None);
+            }
+        } else {
+            writeln!(output, "Waiting for {}'s move...", game.get_player())?;
+            let mut line = String::new();
+            if matches!(peer.read_line(&mut line), Ok(0) | Err(_)) {
+                writeln!(output, "Your opponent disconnected")?;
+                return Ok(None);
+            }
+            let line = line.trim();
+            line.parse::<usize>()
+                .map_err(|err| anyhow!(err))
+                .and_then(|i| Ok(game.make_move(i)?))
+                .map_err(|err| anyhow!("The opponent sent an illegal move {line:?}: {err}"))?;
+        }
+    }
+}
+
+/// Prompts the local player until they play a legal move, or [`None`] if they quit
+fn read_local_move<R: BufRead, W: Write>(
+    game: &mut Game,
+    input: &mut R,
+    output: &mut W,
+) -> Result<Option<usize>> {
+    loop {
+        write!(output, "It's your turn ({}): ", game.get_player())?;
+        output.flush()?;
+        let mut str = String::new();
+        if input.read_line(&mut str)? == 0 {
+            return Ok(None);
+        }
+        let str = str.trim();
+        if matches!(str, "q" | "quit" | "exit") {
+            return Ok(None);
+        }
+        match str
+            .parse::<usize>()
+            .map_err(|err| anyhow!(err))
+            .and_then(|i| Ok(game.make_move(i).map(|_| i)?))
+        {
+            Ok(i) => return Ok(Some(i)),
+            Err(err) => writeln!(output, "{err}")?,
+        }
+    }
+}
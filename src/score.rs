@@ -0,0 +1,64 @@
+//! Running totals over several games
+
+use std::fmt::Display;
+
+use crate::Player;
+
+/// Counts the outcomes of a series of games
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Scoreboard {
+    pub x_wins: usize,
+    pub o_wins: usize,
+    pub draws: usize,
+}
+
+impl Scoreboard {
+    /// Adds the outcome of a finished game, given as the winner or [`None`] for a draw
+    pub fn record(&mut self, outcome: Option<Player>) {
+        match outcome {
+            Some(Player::X) => self.x_wins += 1,
+            Some(Player::O) => self.o_wins += 1,
+            None => self.draws += 1,
+        }
+    }
+}
+
+impl Display for Scoreboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} wins: {}, {} wins: {}, draws: {}",
+            Player::X,
+            self.x_wins,
+            Player::O,
+            self.o_wins,
+            self.draws
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player::*;
+
+    #[test]
+    fn record_outcomes() {
+        let mut scoreboard = Scoreboard::default();
+        for outcome in [Some(X), None, Some(O), Some(X)] {
+            scoreboard.record(outcome);
+        }
+        assert_eq!(
+            Scoreboard {
+                x_wins: 2,
+                o_wins: 1,
+                draws: 1
+            },
+            scoreboard
+        );
+        assert_eq!(
+            format!("{X} wins: 2, {O} wins: 1, draws: 1"),
+            scoreboard.to_string()
+        );
+    }
+}
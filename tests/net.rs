@@ -0,0 +1,47 @@
+use std::{
+    io::{self, Cursor},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+use anyhow::Result;
+
+use tictactoe::{net::play_session, Player};
+
+#[test]
+fn both_sides_agree_on_winner() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let host = thread::spawn(move || -> Result<Option<Player>> {
+        let (stream, _) = listener.accept()?;
+        play_session(stream, Player::X, Cursor::new("1\n2\n3\n"), io::sink())
+    });
+
+    let stream = TcpStream::connect(addr)?;
+    // The illegal 1 is rejected locally and never sent
+    let guest = play_session(stream, Player::O, Cursor::new("1\n4\n5\n"), io::sink())?;
+    let host = host.join().expect("the host thread panicked")?;
+
+    assert_eq!(Some(Player::X), host);
+    assert_eq!(host, guest);
+    Ok(())
+}
+
+#[test]
+fn peer_disconnecting_ends_the_session() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let guest = thread::spawn(move || -> Result<()> {
+        drop(TcpStream::connect(addr)?);
+        Ok(())
+    });
+
+    let (stream, _) = listener.accept()?;
+    guest.join().expect("the guest thread panicked")?;
+    let mut output = Vec::new();
+    let winner = play_session(stream, Player::X, Cursor::new("5\n"), &mut output)?;
+
+    assert_eq!(None, winner);
+    assert!(String::from_utf8(output)?.contains("Your opponent disconnected"));
+    Ok(())
+}
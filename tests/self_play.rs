@@ -0,0 +1,24 @@
+use anyhow::Result;
+
+use tictactoe::{Game, GameStatus};
+
+#[test]
+fn perfect_play_draws() -> Result<()> {
+    let mut game = Game::new();
+    while game.status() == GameStatus::InProgress {
+        let i = game
+            .best_move()
+            .expect("a game in progress has a best move");
+        assert!(game.available_moves().contains(&i));
+        game.play(i)?;
+    }
+    assert_eq!(GameStatus::Drawn, game.status());
+
+    // The tie-break makes the game the same every time
+    let mut again = Game::new();
+    for &i in game.moves() {
+        assert_eq!(Some(i), again.best_move());
+        again.play(i)?;
+    }
+    Ok(())
+}